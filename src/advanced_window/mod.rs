@@ -1,19 +1,13 @@
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::{BufRead, BufReader},
-    path::PathBuf,
-    sync::mpsc,
-    thread
-};
+use std::{collections::HashMap, sync::mpsc, thread};
 
 use adw::{prelude::*, AlertDialog, ResponseAppearance};
 use arboard::Clipboard;
 use gtk::{
     glib::{self, clone, MainContext},
-    ApplicationWindow, Box, Builder, Button, CheckButton, Entry
+    ApplicationWindow, Box, Builder, Button, CheckButton, Entry, Label, Orientation, Widget
 };
-use walkdir::WalkDir;
+
+use crate::{backups, backups_window, game_update, injector_settings, launch_parameters_window};
 
 // Create advanced window
 pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
@@ -66,15 +60,61 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
     // Injector settings box
     let injector_settings_box = builder.object::<Box>("InjectorSettingsBox").unwrap();
 
+    // Box for the settings without their own control
+    let extra_settings_box = builder.object::<Box>("ExtraSettingsBox").unwrap();
+
     // Load injector settings
-    load_injector_settings(&checkboxes, &text_entry, &injector_settings_box);
+    let extra_settings = load_injector_settings(
+        &checkboxes,
+        &text_entry,
+        &injector_settings_box,
+        &extra_settings_box
+    );
+
+    // Init edit game parameters button
+    let edit_parameters_button = builder.object::<Button>("EditGameParameters").unwrap();
+
+    edit_parameters_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        text_entry,
+        move |_| {
+            // Disable advanced window
+            window.set_sensitive(false);
+
+            // Create launch parameters editor
+            let parameters_window = launch_parameters_window::create(
+                &window,
+                &text_entry.text(),
+                clone!(
+                    #[weak]
+                    text_entry,
+                    move |parameters| {
+                        text_entry.set_text(&parameters);
+                    }
+                )
+            );
+
+            // Re-enable advanced window on close
+            parameters_window.connect_destroy(clone!(
+                #[weak]
+                window,
+                move |_| {
+                    window.set_sensitive(true);
+                }
+            ));
+
+            parameters_window.present();
+        }
+    ));
 
     // Init open mods folder button
     let open_mods_button = builder.object::<Button>("OpenEnabled").unwrap();
 
     open_mods_button.connect_clicked(|_| {
         // Open mods folder
-        thread::spawn(|| open::that(crate::GAME_PATH.get().unwrap().join("Mods")));
+        thread::spawn(|| open::that(crate::game_path().join("Mods")));
     });
 
     // Init open disabled mods folder button
@@ -82,7 +122,7 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
 
     open_disabled_button.connect_clicked(|_| {
         // Open disabled mods folder
-        thread::spawn(|| open::that(crate::GAME_PATH.get().unwrap().join("DisabledMods")));
+        thread::spawn(|| open::that(crate::game_path().join("DisabledMods")));
     });
 
     // Init open game folder button
@@ -90,7 +130,7 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
 
     open_game_folder_button.connect_clicked(|_| {
         // Open game folder
-        thread::spawn(|| open::that(crate::GAME_PATH.get().unwrap()));
+        thread::spawn(|| open::that(crate::game_path()));
     });
 
     // Init restore backups button
@@ -142,18 +182,10 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
 
                         let (tx, rx) = mpsc::channel();
 
-                        // Get backups
+                        // Restore backups, then fingerprint the restored game files
                         thread::spawn(move || {
-                            // Backup restored counter
-                            let mut backups_restored = 0_usize;
-
-                            for backup in get_backups() {
-                                // Restore backup
-                                if fs::copy(&backup, &backup.with_extension("")).is_ok() {
-                                    backups_restored += 1;
-                                }
-                            }
-
+                            let backups_restored = backups::restore();
+                            game_update::record();
                             tx.send(backups_restored).unwrap();
                         });
 
@@ -265,17 +297,7 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
 
                         // Get backups
                         thread::spawn(move || {
-                            // Backup deleted counter
-                            let mut backups_deleted = 0_usize;
-
-                            for backup in get_backups() {
-                                // Delete backup
-                                if fs::remove_file(&backup).is_ok() {
-                                    backups_deleted += 1;
-                                }
-                            }
-
-                            tx.send(backups_deleted).unwrap();
+                            tx.send(backups::delete()).unwrap();
                         });
 
                         MainContext::default().spawn_local(clone!(
@@ -335,6 +357,33 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
         }
     ));
 
+    // Init manage backups button
+    let manage_backups_button = builder.object::<Button>("ManageBackups").unwrap();
+
+    manage_backups_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| {
+            // Disable advanced window
+            window.set_sensitive(false);
+
+            // Create backups window
+            let backups_window = backups_window::create(&window);
+
+            // Re-enable advanced window on close
+            backups_window.connect_destroy(clone!(
+                #[weak]
+                window,
+                move |_| {
+                    window.set_sensitive(true);
+                }
+            ));
+
+            // Show backups window
+            backups_window.present();
+        }
+    ));
+
     // Init copy template JSON button
     let copy_template_button = builder.object::<Button>("CopyTemplate").unwrap();
 
@@ -398,7 +447,7 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
             window.set_sensitive(false);
 
             // Save injector settings
-            let message = if save_injector_settings(&checkboxes, &text_entry) {
+            let message = if save_injector_settings(&checkboxes, &text_entry, &extra_settings) {
                 "Successfully saved the new settings."
             }
             else {
@@ -443,226 +492,124 @@ pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
     window
 }
 
-// Get backups in game folder
-fn get_backups() -> Vec<PathBuf> {
-    let mut backups = Vec::new();
-
-    // Check if executable backup exists
-    let exe_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("DOOMEternalx64vk.exe.backup");
-
-    if exe_path.is_file() {
-        // Push to backup list
-        backups.push(exe_path);
-    }
-
-    // Check if packagemapspec backup exists
-    let packagemapspec_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("base")
-        .join("packagemapspec.json.backup");
-
-    if packagemapspec_path.is_file() {
-        // Push to backup list
-        backups.push(packagemapspec_path);
-    }
-
-    // Get backups in "base" directory
-    for backup in fs::read_dir(crate::GAME_PATH.get().unwrap().join("base"))
-        .unwrap()
-        .filter_map(|f| f.ok())
-    {
-        if backup
-            .file_name()
-            .to_str()
-            .unwrap()
-            .ends_with(".resources.backup")
-        {
-            // Push to backup list
-            backups.push(backup.path());
-        }
-    }
-
-    // Get backups in "base/game" directory
-    for backup in WalkDir::new(crate::GAME_PATH.get().unwrap().join("base").join("game"))
-        .into_iter()
-        .filter_map(|f| f.ok())
-    {
-        if backup
-            .file_name()
-            .to_str()
-            .unwrap()
-            .ends_with(".resources.backup")
-        {
-            // Push to backup list
-            backups.push(backup.path().to_path_buf());
-        }
-    }
-
-    // Get backups in "base/sound/soundbanks/pc" directory
-    for backup in fs::read_dir(
-        crate::GAME_PATH
-            .get()
-            .unwrap()
-            .join("base")
-            .join("sound")
-            .join("soundbanks")
-            .join("pc")
-    )
-    .unwrap()
-    .filter_map(|f| f.ok())
-    {
-        if backup.file_name().to_str().unwrap().ends_with(".snd.backup") {
-            // Push to backup list
-            backups.push(backup.path());
-        }
-    }
-
-    backups
-}
-
 // Load injector settings file
+// Returns the controls created for the settings that don't have one in the UI file
 fn load_injector_settings(
-    checkboxes: &HashMap<&str, CheckButton>, text_entry: &Entry, injector_settings_box: &Box
-) {
-    // User settings (found in file)
-    let mut user_settings: HashMap<String, String> = HashMap::new();
-
+    checkboxes: &HashMap<&str, CheckButton>, text_entry: &Entry, injector_settings_box: &Box,
+    extra_settings_box: &Box
+) -> Vec<(String, Widget)> {
     #[cfg(target_os = "windows")]
     // Disable auto update checkbox on Windows
     checkboxes["AUTO_UPDATE"].set_sensitive(false);
 
-    // Get injector settings path
-    let injector_settings_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjector Settings.txt");
-
-    // Open file
-    let injector_settings_file = match File::open(injector_settings_path) {
-        Ok(f) => f,
-        Err(_) => {
+    // Read settings file
+    let settings = match injector_settings::load() {
+        Some(s) => s,
+        None => {
             // Disable settings box
             injector_settings_box.set_sensitive(false);
-            return;
+            return Vec::new();
         }
     };
 
-    // Read settings line by line
-    for line in BufReader::new(injector_settings_file)
-        .lines()
-        .filter_map(|l| l.ok())
-    {
-        // Get only settings
-        if !line.starts_with(':') {
-            continue;
-        }
+    // Check needed checkboxes
+    for (setting, checkbox) in checkboxes {
+        checkbox.set_active(settings.get(setting) == Some("1"));
+    }
 
-        // Split line into key and value
-        let split_line = line.split('=').collect::<Vec<&str>>();
+    // Set game parameters text entry
+    text_entry.set_text(settings.get("GAME_PARAMETERS").unwrap_or_default());
 
-        if split_line.len() != 2 {
+    // Create controls for unknown settings, such as the ones added by newer injectors
+    let mut extra_settings = Vec::new();
+
+    for (setting, value) in settings.settings() {
+        if checkboxes.contains_key(setting)
+            || setting == "GAME_PARAMETERS"
+            || !injector_settings::is_user_setting(setting)
+            || extra_settings.iter().any(|(s, _)| s == setting)
+        {
             continue;
         }
 
-        // Add setting to map
-        let key = &split_line[0][1..];
+        let label = setting_label(setting);
 
-        if checkboxes.contains_key(key) || key == "GAME_PARAMETERS" {
-            user_settings.insert(key.to_owned(), split_line[1].trim().to_owned());
-        }
-    }
+        // Use a checkbox for boolean settings, and a text entry otherwise
+        let control = if value == "0" || value == "1" {
+            let checkbox = CheckButton::builder()
+                .label(&label)
+                .active(value == "1")
+                .tooltip_text(setting)
+                .build();
 
-    // Check needed checkboxes
-    for (setting, value) in &user_settings {
-        // Skip game parameters
-        if setting == "GAME_PARAMETERS" {
-            continue;
+            extra_settings_box.append(&checkbox);
+            checkbox.upcast::<Widget>()
         }
+        else {
+            let row = Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(8)
+                .tooltip_text(setting)
+                .build();
 
-        // Set checkbox
-        checkboxes[setting.as_str()].set_active(value == "1");
-    }
+            row.append(&Label::new(Some(&format!("{}:", label))));
 
-    // Set game parameters text entry
-    text_entry.set_text(&user_settings["GAME_PARAMETERS"]);
-}
+            let entry = Entry::builder().text(value).hexpand(true).build();
+            row.append(&entry);
 
-// Save injector settings file
-fn save_injector_settings(checkboxes: &HashMap<&str, CheckButton>, text_entry: &Entry) -> bool {
-    // Vectors for storing settings
-    let mut settings_file = Vec::new();
-    let mut extra_settings = Vec::new();
+            extra_settings_box.append(&row);
+            entry.upcast::<Widget>()
+        };
 
-    // Get injector settings path
-    let injector_settings_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjector Settings.txt");
-
-    // Open file
-    let injector_settings_file = match File::open(&injector_settings_path) {
-        Ok(f) => f,
-        Err(_) => {
-            return false;
-        }
-    };
+        extra_settings.push((setting.to_owned(), control));
+    }
 
-    // Read settings line by line
-    for line in BufReader::new(&injector_settings_file)
-        .lines()
-        .filter_map(|l| l.ok())
-    {
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
-        }
+    extra_settings_box.set_visible(!extra_settings.is_empty());
 
-        // Add non-settings
-        if !line.starts_with(':') {
-            extra_settings.push(line);
-            continue;
-        }
+    extra_settings
+}
 
-        // Add unknown settings
-        let key = &line.split('=').next().unwrap()[1..];
+// Make a label from a setting's key, such as "Skip intro" for "SKIP_INTRO"
+fn setting_label(setting: &str) -> String {
+    let words = setting.to_lowercase().replace('_', " ");
+    let mut chars = words.chars();
 
-        if !checkboxes.contains_key(key) && key != "GAME_PARAMETERS" {
-            // Add setting to list as-is
-            settings_file.push(line);
-        }
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words
     }
+}
 
-    // Close file
-    drop(injector_settings_file);
+// Save injector settings file
+fn save_injector_settings(
+    checkboxes: &HashMap<&str, CheckButton>, text_entry: &Entry, extra_settings: &[(String, Widget)]
+) -> bool {
+    // Read settings file, keeping unknown settings and other lines
+    let mut settings = match injector_settings::load() {
+        Some(s) => s,
+        None => return false
+    };
 
-    // Add settings to list
-    for (setting, checkbox) in checkboxes {
-        // Get setting value
-        let setting_value = if checkbox.is_active() {
-            String::from("1")
+    // Set checkbox settings, in the order they're shown
+    for setting in injector_settings::USER_SETTINGS {
+        if let Some(checkbox) = checkboxes.get(setting) {
+            settings.set(setting, if checkbox.is_active() { "1" } else { "0" });
         }
-        else {
-            String::from("0")
-        };
-
-        // Add setting
-        settings_file.push(format!(":{}={}", setting, setting_value));
     }
 
-    // Add game parameters setting
-    settings_file.push(format!(":GAME_PARAMETERS={}", text_entry.text()));
-
-    // Append extra settings
-    settings_file.push(String::default());
-    settings_file.append(&mut extra_settings);
+    // Set game parameters setting
+    settings.set("GAME_PARAMETERS", &text_entry.text());
 
-    // Join settings
-    let settings_string = settings_file.join("\n") + "\n";
+    // Set unknown settings
+    for (setting, control) in extra_settings {
+        if let Some(checkbox) = control.downcast_ref::<CheckButton>() {
+            settings.set(setting, if checkbox.is_active() { "1" } else { "0" });
+        }
+        else if let Some(entry) = control.downcast_ref::<Entry>() {
+            settings.set(setting, &entry.text());
+        }
+    }
 
-    // Write new config file
-    fs::write(&injector_settings_path, settings_string).is_ok()
+    // Write new settings file
+    injector_settings::save(&settings)
 }
@@ -0,0 +1,316 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Read, Seek},
+    path::{Path, PathBuf}
+};
+
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+// Highest ratio between an entry's size and its compressed size
+const MAX_COMPRESSION_RATIO: u64 = 1000;
+
+// Entries smaller than this aren't checked for their compression ratio
+const MIN_RATIO_CHECK_SIZE: u64 = 1024 * 1024;
+
+// File type bits of a unix mode, and their value for symbolic links
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+// Reason an archive entry can't be extracted safely
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectReason {
+    AbsolutePath,
+    ParentDirectory,
+    Symlink,
+    CompressionRatio
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            RejectReason::AbsolutePath => "has an absolute path",
+            RejectReason::ParentDirectory => "points outside of the folder it's extracted to",
+            RejectReason::Symlink => "is a symbolic link",
+            RejectReason::CompressionRatio => "is compressed too much to be a real file"
+        };
+
+        write!(f, "{}", description)
+    }
+}
+
+// Error found while inspecting or extracting an archive
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(io::Error),
+    Zip(ZipError),
+    Rejected { entry: String, reason: RejectReason },
+    Encrypted(String),
+    UnsupportedCompression { entry: String, method: String },
+    SizeMismatch(String)
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "The file couldn't be read: {}", e),
+            ArchiveError::Zip(ZipError::InvalidArchive(_)) => {
+                write!(f, "The file isn't a zip archive, or it's damaged.")
+            },
+            ArchiveError::Zip(ZipError::UnsupportedArchive(e)) => {
+                write!(f, "The archive uses an unsupported feature: {}", e)
+            },
+            ArchiveError::Zip(ZipError::Io(e)) => write!(f, "The file couldn't be read: {}", e),
+            ArchiveError::Zip(e) => write!(f, "{}", e),
+            ArchiveError::Rejected { entry, reason } => write!(f, "\"{}\" {}.", entry, reason),
+            ArchiveError::Encrypted(entry) => {
+                write!(f, "\"{}\" is password protected. Mods can't be encrypted.", entry)
+            },
+            ArchiveError::UnsupportedCompression { entry, method } => write!(
+                f,
+                "\"{}\" uses the {} compression method, which isn't supported. Compress it with Deflate \
+                 instead.",
+                entry, method
+            ),
+            ArchiveError::SizeMismatch(entry) => {
+                write!(f, "\"{}\" is larger than the archive says.", entry)
+            }
+        }
+    }
+}
+
+// Get the relative path an entry is extracted to, checking that it stays inside the destination
+fn entry_path(name: &str) -> Result<PathBuf, RejectReason> {
+    if name.starts_with(['/', '\\']) {
+        return Err(RejectReason::AbsolutePath);
+    }
+
+    let mut path = PathBuf::new();
+
+    // Archives made on Windows can use either separator
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => {},
+            ".." => return Err(RejectReason::ParentDirectory),
+            // Pushing a drive prefix like "C:" anywhere in the path replaces it on Windows
+            c if c.contains(':') => return Err(RejectReason::AbsolutePath),
+            c => path.push(c)
+        }
+    }
+
+    Ok(path)
+}
+
+// Check an entry before it's extracted, returning the relative path to extract it to
+fn check_entry(
+    name: &str, unix_mode: Option<u32>, size: u64, compressed_size: u64
+) -> Result<PathBuf, RejectReason> {
+    if unix_mode.is_some_and(|m| m & S_IFMT == S_IFLNK) {
+        return Err(RejectReason::Symlink);
+    }
+
+    // Catch zip bombs without flagging small, highly compressible files
+    if size >= MIN_RATIO_CHECK_SIZE && size / compressed_size.max(1) > MAX_COMPRESSION_RATIO {
+        return Err(RejectReason::CompressionRatio);
+    }
+
+    entry_path(name)
+}
+
+// Get the name of a compression method the zip crate can't decompress
+#[allow(deprecated)]
+fn unsupported_method_name(method: CompressionMethod) -> Option<String> {
+    let id = match method {
+        CompressionMethod::Unsupported(id) => id,
+        _ => return None
+    };
+
+    let name = match id {
+        1 => "Shrink",
+        2..=5 => "Reduce",
+        6 | 10 => "Implode",
+        95 => "XZ",
+        97 => "WavPack",
+        98 => "PPMd",
+        _ => return Some(format!("unknown ({})", id))
+    };
+
+    Some(name.to_owned())
+}
+
+// Check every entry of an archive without extracting anything
+pub fn inspect<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<(), ArchiveError> {
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(ArchiveError::Zip)?;
+
+        // Check for entries that can't be read
+        if entry.encrypted() {
+            return Err(ArchiveError::Encrypted(entry.name().to_owned()));
+        }
+
+        if let Some(method) = unsupported_method_name(entry.compression()) {
+            return Err(ArchiveError::UnsupportedCompression {
+                entry: entry.name().to_owned(),
+                method
+            });
+        }
+
+        if let Err(reason) = check_entry(
+            entry.name(),
+            entry.unix_mode(),
+            entry.size(),
+            entry.compressed_size()
+        ) {
+            return Err(ArchiveError::Rejected {
+                entry: entry.name().to_owned(),
+                reason
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Open and inspect an archive
+pub fn open(path: &Path) -> Result<ZipArchive<File>, ArchiveError> {
+    let file = File::open(path).map_err(ArchiveError::Io)?;
+    let mut archive = ZipArchive::new(file).map_err(ArchiveError::Zip)?;
+
+    inspect(&mut archive)?;
+    Ok(archive)
+}
+
+// Extract an archive into a folder, after checking that all of its entries are safe
+pub fn extract<R: Read + Seek>(archive: &mut ZipArchive<R>, destination: &Path) -> Result<(), ArchiveError> {
+    // Don't write anything if any entry is unsafe
+    inspect(archive)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(ArchiveError::Zip)?;
+        let name = entry.name().to_owned();
+
+        let path = destination.join(entry_path(&name).map_err(|reason| ArchiveError::Rejected {
+            entry: name.clone(),
+            reason
+        })?);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(ArchiveError::Io)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(ArchiveError::Io)?;
+        }
+
+        // Don't trust the declared size
+        let size = entry.size();
+        let mut output = File::create(&path).map_err(ArchiveError::Io)?;
+        let written = io::copy(&mut (&mut entry).take(size + 1), &mut output).map_err(ArchiveError::Io)?;
+
+        if written > size {
+            drop(output);
+            let _ = fs::remove_file(&path);
+            return Err(ArchiveError::SizeMismatch(name));
+        }
+
+        // Keep scripts executable
+        #[cfg(target_os = "linux")]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o777)).map_err(ArchiveError::Io)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths_are_kept() {
+        assert_eq!(
+            entry_path("base/game/mod.resources"),
+            Ok(["base", "game", "mod.resources"].iter().collect::<PathBuf>())
+        );
+        assert_eq!(
+            entry_path("base\\game\\.\\mod.resources"),
+            Ok(["base", "game", "mod.resources"].iter().collect::<PathBuf>())
+        );
+        assert_eq!(entry_path("tools//"), Ok(PathBuf::from("tools")));
+    }
+
+    #[test]
+    fn absolute_paths_are_rejected() {
+        assert_eq!(entry_path("/etc/passwd"), Err(RejectReason::AbsolutePath));
+        assert_eq!(entry_path("\\Windows\\evil.dll"), Err(RejectReason::AbsolutePath));
+        assert_eq!(entry_path("C:/evil"), Err(RejectReason::AbsolutePath));
+        assert_eq!(entry_path("C:evil"), Err(RejectReason::AbsolutePath));
+    }
+
+    #[test]
+    fn drive_prefixes_inside_paths_are_rejected() {
+        assert_eq!(entry_path("tools/C:evil"), Err(RejectReason::AbsolutePath));
+        assert_eq!(entry_path("a\\C:x"), Err(RejectReason::AbsolutePath));
+        assert_eq!(entry_path("a/b/D:/x"), Err(RejectReason::AbsolutePath));
+    }
+
+    #[test]
+    fn parent_directories_are_rejected() {
+        assert_eq!(entry_path("../evil"), Err(RejectReason::ParentDirectory));
+        assert_eq!(entry_path("base/../../evil"), Err(RejectReason::ParentDirectory));
+        assert_eq!(entry_path("base\\..\\evil"), Err(RejectReason::ParentDirectory));
+    }
+
+    #[test]
+    fn symlinks_are_rejected() {
+        assert_eq!(
+            check_entry("link", Some(S_IFLNK | 0o777), 10, 10),
+            Err(RejectReason::Symlink)
+        );
+        assert_eq!(
+            check_entry("file", Some(0o100644), 10, 10),
+            Ok(PathBuf::from("file"))
+        );
+        assert_eq!(check_entry("file", None, 10, 10), Ok(PathBuf::from("file")));
+    }
+
+    #[test]
+    fn compression_ratio_is_checked_for_large_entries() {
+        let size = MIN_RATIO_CHECK_SIZE * 4;
+
+        assert_eq!(
+            check_entry("bomb", None, size, size / (MAX_COMPRESSION_RATIO * 2)),
+            Err(RejectReason::CompressionRatio)
+        );
+        assert_eq!(
+            check_entry("bomb", None, size, 0),
+            Err(RejectReason::CompressionRatio)
+        );
+        assert_eq!(
+            check_entry("file", None, size, size / 2),
+            Ok(PathBuf::from("file"))
+        );
+
+        // Small files can be compressed a lot
+        assert_eq!(
+            check_entry("small", None, MIN_RATIO_CHECK_SIZE - 1, 1),
+            Ok(PathBuf::from("small"))
+        );
+    }
+
+    #[test]
+    fn entries_are_checked_after_their_type() {
+        assert_eq!(
+            check_entry("../evil", Some(S_IFLNK), 10, 10),
+            Err(RejectReason::Symlink)
+        );
+        assert_eq!(
+            check_entry("../evil", None, 10, 10),
+            Err(RejectReason::ParentDirectory)
+        );
+    }
+}
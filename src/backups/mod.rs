@@ -0,0 +1,135 @@
+use std::{fs, io, path::PathBuf, time::SystemTime};
+
+use walkdir::WalkDir;
+
+// Game file backed up by the mod injector
+#[derive(Clone, Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub target: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub orphaned: bool
+}
+
+impl Backup {
+    // Read a backup's details
+    fn new(path: PathBuf) -> Backup {
+        let metadata = fs::metadata(&path).ok();
+        let target = path.with_extension("");
+
+        Backup {
+            size: metadata.as_ref().map(|m| m.len()).unwrap_or_default(),
+            modified: metadata.and_then(|m| m.modified().ok()),
+            orphaned: !target.exists(),
+            target,
+            path
+        }
+    }
+
+    // Get the original file's path relative to the game folder
+    pub fn target_name(&self) -> String {
+        self.target
+            .strip_prefix(crate::game_path())
+            .unwrap_or(&self.target)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    // Copy the backup over the original file
+    pub fn restore(&self) -> io::Result<()> {
+        fs::copy(&self.path, &self.target).map(|_| ())
+    }
+
+    // Delete the backup
+    pub fn delete(&self) -> io::Result<()> {
+        fs::remove_file(&self.path)
+    }
+}
+
+// Get backups in game folder
+fn get_backups() -> Vec<PathBuf> {
+    let mut backups = Vec::new();
+
+    // Check if executable backup exists
+    let exe_path = crate::game_path().join("DOOMEternalx64vk.exe.backup");
+
+    if exe_path.is_file() {
+        // Push to backup list
+        backups.push(exe_path);
+    }
+
+    // Check if packagemapspec backup exists
+    let packagemapspec_path = crate::game_path().join("base").join("packagemapspec.json.backup");
+
+    if packagemapspec_path.is_file() {
+        // Push to backup list
+        backups.push(packagemapspec_path);
+    }
+
+    // Get backups in "base" directory
+    for backup in fs::read_dir(crate::game_path().join("base"))
+        .unwrap()
+        .filter_map(|f| f.ok())
+    {
+        if backup
+            .file_name()
+            .to_str()
+            .unwrap()
+            .ends_with(".resources.backup")
+        {
+            // Push to backup list
+            backups.push(backup.path());
+        }
+    }
+
+    // Get backups in "base/game" directory
+    for backup in WalkDir::new(crate::game_path().join("base").join("game"))
+        .into_iter()
+        .filter_map(|f| f.ok())
+    {
+        if backup
+            .file_name()
+            .to_str()
+            .unwrap()
+            .ends_with(".resources.backup")
+        {
+            // Push to backup list
+            backups.push(backup.path().to_path_buf());
+        }
+    }
+
+    // Get backups in "base/sound/soundbanks/pc" directory
+    for backup in fs::read_dir(
+        crate::game_path()
+            .join("base")
+            .join("sound")
+            .join("soundbanks")
+            .join("pc")
+    )
+    .unwrap()
+    .filter_map(|f| f.ok())
+    {
+        if backup.file_name().to_str().unwrap().ends_with(".snd.backup") {
+            // Push to backup list
+            backups.push(backup.path());
+        }
+    }
+
+    backups
+}
+
+// Get the details of every backup in the game folder
+pub fn list() -> Vec<Backup> {
+    get_backups().into_iter().map(Backup::new).collect()
+}
+
+// Restore every backup, returning how many were restored
+pub fn restore() -> usize {
+    list().iter().filter(|b| b.restore().is_ok()).count()
+}
+
+// Delete every backup, returning how many were deleted
+pub fn delete() -> usize {
+    list().iter().filter(|b| b.delete().is_ok()).count()
+}
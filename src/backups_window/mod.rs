@@ -0,0 +1,336 @@
+use std::{io, sync::mpsc, thread, time::UNIX_EPOCH};
+
+use adw::{prelude::*, AlertDialog, ResponseAppearance};
+use gtk::{
+    glib::{self, clone, DateTime, MainContext},
+    pango::EllipsizeMode,
+    Align, ApplicationWindow, Box, Builder, Button, Label, ListBox, ListBoxRow, Orientation
+};
+
+use crate::{
+    backups::{self, Backup},
+    game_update,
+    manager_window::show_message
+};
+
+// Create backups window
+pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
+    // Create builder from UI file
+    let ui_src = include_str!("backups.ui");
+    let builder = Builder::from_string(ui_src);
+
+    // Get window
+    let window = builder.object::<ApplicationWindow>("BackupsWindow").unwrap();
+    window.set_transient_for(Some(parent_window));
+
+    // Fill backup list
+    let listbox = builder.object::<ListBox>("BackupList").unwrap();
+    let summary = builder.object::<Label>("BackupsSummary").unwrap();
+    load_backups(&window, &listbox, &summary);
+
+    window
+}
+
+// Find the backups in the background and fill the listbox with them
+fn load_backups(window: &ApplicationWindow, listbox: &ListBox, summary: &Label) {
+    // Disable window
+    window.set_sensitive(false);
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        tx.send(backups::list()).unwrap();
+    });
+
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[weak]
+        summary,
+        async move {
+            if let Ok(backups) = rx.recv() {
+                window.set_sensitive(true);
+                fill_backup_list(&window, &listbox, &summary, backups);
+            }
+        }
+    ));
+}
+
+// Fill the listbox with the backups
+fn fill_backup_list(window: &ApplicationWindow, listbox: &ListBox, summary: &Label, backups: Vec<Backup>) {
+    listbox.remove_all();
+
+    if backups.is_empty() {
+        summary.set_text("No backups found.");
+
+        let empty_label = Label::builder()
+            .label("The mod injector hasn't backed up any game files yet.")
+            .margin_top(12)
+            .margin_bottom(12)
+            .build();
+        empty_label.add_css_class("dim-label");
+
+        listbox.append(
+            &ListBoxRow::builder()
+                .child(&empty_label)
+                .activatable(false)
+                .build()
+        );
+        return;
+    }
+
+    // Show totals
+    let total_size = backups.iter().map(|b| b.size).sum::<u64>();
+    let orphaned = backups.iter().filter(|b| b.orphaned).count();
+
+    let mut summary_text = format!(
+        "{} backups using {}.",
+        backups.len(),
+        glib::format_size(total_size)
+    );
+
+    if orphaned > 0 {
+        summary_text += &format!(" {} backups belong to files that no longer exist.", orphaned);
+    }
+
+    summary.set_text(&summary_text);
+
+    // Show orphaned backups first
+    let mut backups = backups;
+    backups.sort_by(|a, b| b.orphaned.cmp(&a.orphaned).then_with(|| a.target.cmp(&b.target)));
+
+    for backup in backups {
+        listbox.append(&create_row(window, listbox, summary, backup));
+    }
+}
+
+// Create a list row for a backup
+fn create_row(window: &ApplicationWindow, listbox: &ListBox, summary: &Label, backup: Backup) -> ListBoxRow {
+    // Create row contents
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+
+    let label_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .hexpand(true)
+        .build();
+    row_box.append(&label_box);
+
+    let target_name = backup.target_name();
+
+    let name = Label::builder()
+        .label(&target_name)
+        .tooltip_text(backup.path.to_string_lossy())
+        .halign(Align::Start)
+        .ellipsize(EllipsizeMode::Start)
+        .build();
+    name.add_css_class("heading");
+    label_box.append(&name);
+
+    // Show size and modification time
+    let modified = backup
+        .modified
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .and_then(|d| DateTime::from_unix_local(d.as_secs() as i64).ok())
+        .and_then(|d| d.format("%Y-%m-%d %H:%M").ok())
+        .map(|d| d.to_string())
+        .unwrap_or_else(|| String::from("Unknown date"));
+
+    let details = Label::builder()
+        .label(format!("{} · {}", glib::format_size(backup.size), modified))
+        .halign(Align::Start)
+        .build();
+    details.add_css_class("dim-label");
+    label_box.append(&details);
+
+    // Flag backups whose original file is gone
+    if backup.orphaned {
+        let orphaned_label = Label::builder()
+            .label("Orphaned: the original file no longer exists.")
+            .halign(Align::Start)
+            .build();
+        orphaned_label.add_css_class("warning");
+        label_box.append(&orphaned_label);
+    }
+
+    let restore_button = Button::with_label("Restore");
+    restore_button.set_valign(Align::Center);
+    restore_button.set_tooltip_text(Some("Copy the backup over the original file"));
+    row_box.append(&restore_button);
+
+    let delete_button = Button::from_icon_name("user-trash-symbolic");
+    delete_button.set_valign(Align::Center);
+    delete_button.set_tooltip_text(Some("Delete backup"));
+    row_box.append(&delete_button);
+
+    // Restore backup after confirmation
+    restore_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[weak]
+        summary,
+        #[strong]
+        backup,
+        #[strong]
+        target_name,
+        move |_| {
+            confirm(
+                &window,
+                "Restore backup?",
+                &format!("\"{}\" will be replaced with its unmodded backup.", target_name),
+                "_Restore",
+                clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    listbox,
+                    #[weak]
+                    summary,
+                    #[strong]
+                    backup,
+                    move || {
+                        let backup = backup.clone();
+                        run_backup_action(&window, &listbox, &summary, "restore", move || {
+                            // Fingerprint the restored game files
+                            backup.restore()?;
+                            game_update::record();
+                            Ok(())
+                        });
+                    }
+                )
+            );
+        }
+    ));
+
+    // Delete backup after confirmation
+    delete_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[weak]
+        summary,
+        #[strong]
+        backup,
+        move |_| {
+            let body = if backup.orphaned {
+                format!(
+                    "The backup of \"{}\" will be deleted. The original file no longer exists.",
+                    target_name
+                )
+            }
+            else {
+                format!(
+                    "The backup of \"{}\" will be deleted. You won't be able to restore the original file \
+                     without verifying the game files.",
+                    target_name
+                )
+            };
+
+            confirm(
+                &window,
+                "Delete backup?",
+                &body,
+                "_Delete",
+                clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    listbox,
+                    #[weak]
+                    summary,
+                    #[strong]
+                    backup,
+                    move || {
+                        let backup = backup.clone();
+                        run_backup_action(&window, &listbox, &summary, "delete", move || backup.delete());
+                    }
+                )
+            );
+        }
+    ));
+
+    ListBoxRow::builder().child(&row_box).activatable(false).build()
+}
+
+// Restore or delete a backup in the background, then reload the list
+fn run_backup_action(
+    window: &ApplicationWindow, listbox: &ListBox, summary: &Label, verb: &'static str,
+    action: impl FnOnce() -> io::Result<()> + Send + 'static
+) {
+    // Disable window
+    window.set_sensitive(false);
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        tx.send(action()).unwrap();
+    });
+
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[weak]
+        summary,
+        async move {
+            if let Ok(result) = rx.recv() {
+                if let Err(e) = result {
+                    show_message(
+                        &window,
+                        &format!("Failed to {} backup.", verb),
+                        &format!("{}.", e)
+                    );
+                }
+
+                load_backups(&window, &listbox, &summary);
+            }
+        }
+    ));
+}
+
+// Ask for confirmation, calling on_confirm if the user accepts
+fn confirm(
+    window: &ApplicationWindow, heading: &str, body: &str, confirm_label: &str,
+    on_confirm: impl Fn() + 'static
+) {
+    let dialog = AlertDialog::builder()
+        .heading(heading)
+        .body(body)
+        .default_response("no")
+        .close_response("no")
+        .build();
+
+    dialog.add_responses(&[("yes", confirm_label), ("no", "_Cancel")]);
+    dialog.set_response_appearance("yes", ResponseAppearance::Destructive);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(None, move |_, result| {
+        if result == "yes" {
+            on_confirm();
+        }
+    });
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(window));
+}
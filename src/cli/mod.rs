@@ -0,0 +1,353 @@
+use std::{env, fs, path::Path};
+
+use gtk::glib::ExitCode;
+use serde_json::json;
+
+use crate::{backups, config, game_update, injector, injector_settings, mods, online_safety, steam};
+
+// Commands handled without opening a window
+const COMMANDS: [&str; 9] = [
+    "list",
+    "enable",
+    "disable",
+    "install",
+    "inject",
+    "restore-backups",
+    "settings",
+    "help",
+    "--help"
+];
+
+// Usage message
+const USAGE: &str = "Usage: eternalmodmanager [--game-path <path>] <command> [arguments]
+
+Commands:
+  list [--json]                 List the installed mods
+  enable <mod>                  Enable a mod
+  disable <mod>                 Disable a mod
+  install <zip>...              Copy mods into the Mods folder
+  inject                        Run the mod injector
+  restore-backups               Restore the game files backed up by the mod injector
+  settings get [key]            Show the mod injector settings
+  settings set <key> <value>    Change a mod injector setting
+
+Running without a command opens the manager window.";
+
+// Run a command from the command line
+// Returns None if the arguments don't contain a command, so the window should be opened instead
+pub fn run(args: &[String]) -> Option<ExitCode> {
+    let mut args = args.iter().skip(1).map(String::as_str).collect::<Vec<&str>>();
+
+    // Get game path from arguments
+    let mut game_path_arg = None;
+
+    if args.first() == Some(&"--game-path") {
+        if args.len() < 2 {
+            attach_console();
+            eprintln!("{}", USAGE);
+            return Some(ExitCode::FAILURE);
+        }
+
+        game_path_arg = Some(args[1].to_owned());
+        args.drain(..2);
+    }
+
+    // Open the window if there's no command
+    match args.first() {
+        Some(command) if COMMANDS.contains(command) => {},
+        _ if game_path_arg.is_none() => return None,
+        _ => {
+            attach_console();
+            eprintln!("{}", USAGE);
+            return Some(ExitCode::FAILURE);
+        }
+    }
+
+    attach_console();
+
+    if matches!(args[0], "help" | "--help") {
+        println!("{}", USAGE);
+        return Some(ExitCode::SUCCESS);
+    }
+
+    // Set game path
+    if let Err(e) = init_game_path(game_path_arg.as_deref()) {
+        eprintln!("Error: {}", e);
+        return Some(ExitCode::FAILURE);
+    }
+
+    // Load online safety rules
+    if let Some(err) = online_safety::init_rules() {
+        eprintln!("Warning: Using the default online safety rules. {}", err);
+    }
+
+    let result = match (args[0], &args[1..]) {
+        ("list", []) => list(false),
+        ("list", ["--json"]) => list(true),
+        ("enable", [filename]) => set_enabled(filename, true),
+        ("disable", [filename]) => set_enabled(filename, false),
+        ("install", paths) if !paths.is_empty() => install(paths),
+        ("inject", []) => return Some(inject()),
+        ("restore-backups", []) => {
+            println!("{} backups were restored.", backups::restore());
+            game_update::record();
+            Ok(())
+        },
+        ("settings", ["get"]) => get_settings(None),
+        ("settings", ["get", key]) => get_settings(Some(key)),
+        ("settings", ["set", key, value]) => set_setting(key, value),
+        _ => Err(format!("Invalid arguments.\n\n{}", USAGE))
+    };
+
+    match result {
+        Ok(()) => Some(ExitCode::SUCCESS),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            Some(ExitCode::FAILURE)
+        }
+    }
+}
+
+// Attach to the parent's console on Windows, since the app doesn't open one
+fn attach_console() {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+// Set the game path from the arguments, the config file, the current directory or the Steam libraries
+fn init_game_path(game_path_arg: Option<&str>) -> Result<(), String> {
+    let game_path = match game_path_arg {
+        Some(path) if mods::is_game_folder(Path::new(path)) => Path::new(path).to_path_buf(),
+        Some(path) => return Err(format!("\"{}\" is not a DOOM Eternal folder.", path)),
+        None => {
+            let config_path = config::load()
+                .string("settings", "game-path")
+                .ok()
+                .map(|p| Path::new(p.as_str()).to_path_buf())
+                .filter(|p| mods::is_game_folder(p));
+
+            let current_dir = || env::current_dir().ok().filter(|p| mods::is_game_folder(p));
+
+            // Only use the Steam libraries if there's a single install
+            let steam_folder = || {
+                let mut game_folders = steam::find_game_folders();
+                (game_folders.len() == 1).then(|| game_folders.remove(0))
+            };
+
+            config_path
+                .or_else(current_dir)
+                .or_else(steam_folder)
+                .ok_or("Couldn't find the game folder. Use --game-path to set it.")?
+        }
+    };
+
+    // Create the mod directories
+    for folder in ["Mods", "DisabledMods"] {
+        fs::create_dir_all(game_path.join(folder))
+            .map_err(|e| format!("Failed to create the {} folder: {}.", folder, e))?;
+    }
+
+    crate::set_game_path(game_path);
+    Ok(())
+}
+
+// Print the installed mods
+fn list(as_json: bool) -> Result<(), String> {
+    let mod_list = mods::scan();
+
+    if as_json {
+        let json_mods = mod_list
+            .iter()
+            .map(|m| {
+                json!({
+                    "filename": m.filename(),
+                    "name": m.name(),
+                    "enabled": m.is_enabled(),
+                    "valid": m.is_valid(),
+                    "invalidReason": m.invalid_reason(),
+                    "onlineSafe": m.is_valid() && m.is_online_safe(),
+                    "compatible": m.is_compatible(),
+                    "loadPriority": m.eternal_mod().load_priority,
+                    "conflicts": m.conflicts(),
+                    "warnings": m
+                        .warnings()
+                        .unwrap_or_default()
+                        .lines()
+                        .collect::<Vec<&str>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!("{}", serde_json::to_string_pretty(&json_mods).unwrap());
+        return Ok(());
+    }
+
+    for mod_data in &mod_list {
+        let safety = if !mod_data.is_valid() {
+            "invalid"
+        }
+        else if mod_data.is_online_safe() {
+            "safe"
+        }
+        else {
+            "unsafe"
+        };
+
+        println!(
+            "[{}] {:<7} {}",
+            if mod_data.is_enabled() { "x" } else { " " },
+            safety,
+            mod_data.filename().unwrap()
+        );
+    }
+
+    Ok(())
+}
+
+// Enable or disable a mod
+fn set_enabled(filename: &str, enabled: bool) -> Result<(), String> {
+    // Make sure the argument is a mod filename, not a path
+    if Path::new(filename).file_name().is_none_or(|f| f != filename) {
+        return Err(format!("\"{}\" is not a mod filename.", filename));
+    }
+
+    let (from, to) = if enabled {
+        ("DisabledMods", "Mods")
+    }
+    else {
+        ("Mods", "DisabledMods")
+    };
+
+    let game_path = crate::game_path();
+
+    if game_path.join(to).join(filename).is_file() {
+        println!(
+            "\"{}\" is already {}.",
+            filename,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        return Ok(());
+    }
+
+    if !game_path.join(from).join(filename).is_file() {
+        return Err(format!("\"{}\" is not installed.", filename));
+    }
+
+    mods::set_enabled(filename, enabled).map_err(|e| format!("Failed to move \"{}\": {}.", filename, e))?;
+
+    println!(
+        "\"{}\" was {}.",
+        filename,
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+// Install mods from zip files
+fn install(paths: &[&str]) -> Result<(), String> {
+    for path in paths {
+        let new_path =
+            mods::install(Path::new(path)).map_err(|e| format!("Failed to install \"{}\": {}.", path, e))?;
+
+        println!(
+            "Installed \"{}\".",
+            new_path.file_name().unwrap().to_string_lossy()
+        );
+    }
+
+    Ok(())
+}
+
+// Run the mod injector in the current terminal
+fn inject() -> ExitCode {
+    let result = injector::run_attached();
+
+    // The injector modified the game files, so fingerprint them again
+    game_update::record();
+
+    match result {
+        Ok(Some(code)) => ExitCode::from(code),
+        Ok(None) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: Failed to run the mod injector: {}.", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Print the mod injector settings
+fn get_settings(key: Option<&str>) -> Result<(), String> {
+    let settings =
+        injector_settings::read_user_settings().ok_or("The mod injector settings file was not found.")?;
+
+    match key {
+        Some(key) => {
+            let key = key.to_uppercase();
+            let value = settings.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+
+            if value.is_none() && !injector_settings::USER_SETTINGS.contains(&key.as_str()) {
+                return Err(format!("Unknown setting \"{}\".", key));
+            }
+
+            println!("{}", value.unwrap_or_default());
+        },
+        None => {
+            for (key, value) in settings {
+                println!("{}={}", key, value);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+// Change a mod injector setting
+// Settings the app doesn't know about can be changed too, like in the advanced options
+fn set_setting(key: &str, value: &str) -> Result<(), String> {
+    let key = key.to_uppercase();
+    let mut settings = injector_settings::load().ok_or("The mod injector settings file was not found.")?;
+    let current = settings.get(&key);
+
+    let is_known = injector_settings::USER_SETTINGS.contains(&key.as_str());
+
+    if !is_known && (current.is_none() || !injector_settings::is_user_setting(&key)) {
+        let mut valid_settings = injector_settings::USER_SETTINGS.to_vec();
+
+        valid_settings.extend(settings.settings().map(|(k, _)| k).filter(|k| {
+            injector_settings::is_user_setting(k) && !injector_settings::USER_SETTINGS.contains(k)
+        }));
+
+        return Err(format!(
+            "Unknown setting \"{}\". Valid settings are: {}.",
+            key,
+            valid_settings.join(", ")
+        ));
+    }
+
+    // Checkbox settings must be 0 or 1, and unknown settings are checkboxes if they're 0 or 1
+    let is_checkbox = if is_known {
+        key != "GAME_PARAMETERS"
+    }
+    else {
+        matches!(current, Some("0" | "1"))
+    };
+
+    if is_checkbox && value != "0" && value != "1" {
+        return Err(format!("{} must be 0 or 1.", key));
+    }
+
+    settings.set(&key, value);
+
+    if !injector_settings::save(&settings) {
+        return Err(String::from(
+            "The mod injector settings file couldn't be written."
+        ));
+    }
+
+    println!("{}={}", key, value);
+    Ok(())
+}
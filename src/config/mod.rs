@@ -0,0 +1,92 @@
+use std::{
+    fs,
+    path::{Path, PathBuf}
+};
+
+use gtk::glib::{self, KeyFile, KeyFileFlags};
+use sha2::{Digest, Sha256};
+
+// Get the app's config directory
+pub fn config_dir() -> PathBuf {
+    glib::user_config_dir().join("EternalModManager")
+}
+
+// Get a short ID for a game folder, to keep the data of each install apart
+pub fn install_id(game_path: &Path) -> String {
+    let hash = Sha256::digest(game_path.to_string_lossy().as_bytes());
+    format!("{:x}", hash)[..16].to_owned()
+}
+
+// Read the game folders stored in a config file, most recent first
+fn read_game_paths(keyfile: &KeyFile) -> Vec<PathBuf> {
+    keyfile
+        .keys("game-paths")
+        .map(|keys| {
+            keys.iter()
+                .filter_map(|k| keyfile.string("game-paths", k).ok())
+                .map(|p| PathBuf::from(p.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Get the game folders the user has used, most recent first
+pub fn game_paths() -> Vec<PathBuf> {
+    read_game_paths(&load_user())
+}
+
+// Add a game folder to the top of the used game folders
+pub fn add_game_path(keyfile: &KeyFile, game_path: &Path) {
+    let mut game_paths = read_game_paths(keyfile);
+    game_paths.retain(|p| p != game_path);
+    game_paths.insert(0, game_path.to_path_buf());
+
+    // Rewrite the group to keep the order
+    let _ = keyfile.remove_group("game-paths");
+
+    for (i, path) in game_paths.iter().enumerate() {
+        keyfile.set_string("game-paths", &format!("path{}", i), &path.to_string_lossy());
+    }
+}
+
+// Load the config file, searching the system config dirs too
+pub fn load() -> KeyFile {
+    let keyfile = KeyFile::new();
+    let mut config_dirs = vec![glib::user_config_dir()];
+    config_dirs.append(&mut glib::system_config_dirs());
+
+    let _ = keyfile.load_from_dirs(
+        "EternalModManager/config",
+        &config_dirs,
+        KeyFileFlags::KEEP_COMMENTS
+    );
+    keyfile
+}
+
+// Load the user's config file only, so it can be modified and saved
+pub fn load_user() -> KeyFile {
+    let keyfile = KeyFile::new();
+    let _ = keyfile.load_from_file(config_dir().join("config"), KeyFileFlags::KEEP_COMMENTS);
+    keyfile
+}
+
+// Save the user's config file
+pub fn save(keyfile: &KeyFile) -> bool {
+    let config_dir = config_dir();
+
+    // Create directory if necessary
+    if !config_dir.exists() {
+        if fs::create_dir_all(&config_dir).is_err() {
+            return false;
+        }
+    }
+    else if config_dir.is_file() {
+        return false;
+    }
+
+    if !config_dir.is_dir() {
+        return false;
+    }
+
+    keyfile.save_to_file(config_dir.join("config")).is_ok()
+}
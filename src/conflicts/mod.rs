@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use crate::mod_data::ModData;
+
+// Enabled mod that modifies a contested file
+#[derive(Clone, Debug)]
+pub struct ConflictingMod {
+    pub filename: String,
+    pub load_priority: i64
+}
+
+// File modified by more than one enabled mod
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    pub path: String,
+    pub mods: Vec<ConflictingMod>
+}
+
+impl Conflict {
+    // Get the mod whose version of the file gets loaded, if it can be determined
+    // Mods with a lower load priority are loaded last, overwriting the rest
+    pub fn winner(&self) -> Option<&ConflictingMod> {
+        let winner = self.mods.iter().min_by_key(|m| m.load_priority)?;
+
+        // Mods with the same load priority are loaded in an unspecified order
+        if self
+            .mods
+            .iter()
+            .filter(|m| m.load_priority == winner.load_priority)
+            .count()
+            > 1
+        {
+            return None;
+        }
+
+        Some(winner)
+    }
+}
+
+// Index of files modified by more than one enabled mod
+#[derive(Debug, Default)]
+pub struct ConflictIndex {
+    pub conflicts: Vec<Conflict>
+}
+
+impl ConflictIndex {
+    // Build the conflict index from the enabled mods
+    pub fn build<'a>(mods: impl IntoIterator<Item = &'a ModData>) -> ConflictIndex {
+        let mut files: BTreeMap<String, Conflict> = BTreeMap::new();
+
+        for mod_data in mods {
+            // Skip disabled and invalid mods
+            if !mod_data.is_enabled() || !mod_data.is_valid() {
+                continue;
+            }
+
+            let mod_entry = ConflictingMod {
+                filename: mod_data.filename().unwrap(),
+                load_priority: mod_data.eternal_mod().load_priority.unwrap_or(0)
+            };
+
+            // Add mod to the entries it modifies
+            for entry in mod_data.entries() {
+                files
+                    .entry(entry.to_lowercase())
+                    .or_insert_with(|| Conflict {
+                        path: entry.clone(),
+                        mods: Vec::new()
+                    })
+                    .mods
+                    .push(mod_entry.clone());
+            }
+        }
+
+        // Keep only contested files
+        let conflicts = files.into_values().filter(|c| c.mods.len() > 1).collect();
+
+        ConflictIndex { conflicts }
+    }
+
+    // Get the number of contested files modified by the given mod
+    pub fn conflict_count(&self, filename: &str) -> u32 {
+        self.conflicts
+            .iter()
+            .filter(|c| c.mods.iter().any(|m| m.filename == filename))
+            .count() as u32
+    }
+}
+
+// Check if a zip entry overwrites a game file
+pub fn is_game_file_entry(entry: &str) -> bool {
+    let entry = entry.to_lowercase();
+
+    // Skip directories and top-level files
+    if entry.ends_with('/') || !entry.contains('/') {
+        return false;
+    }
+
+    // Skip hidden system files
+    if entry.ends_with("desktop.ini") || entry.ends_with(".ds_store") {
+        return false;
+    }
+
+    // Skip mod metadata
+    !entry.starts_with("eternalmod/")
+}
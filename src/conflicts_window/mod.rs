@@ -0,0 +1,108 @@
+use adw::prelude::*;
+use gtk::{glib, pango::EllipsizeMode, Align, ApplicationWindow, Box, Builder, Label, ListBox, Orientation};
+
+use crate::{
+    conflicts::{Conflict, ConflictIndex},
+    model::Model
+};
+
+// Create conflicts window
+pub fn create(parent_window: &ApplicationWindow, model: &Model) -> ApplicationWindow {
+    // Create builder from UI file
+    let ui_src = include_str!("conflicts.ui");
+    let builder = Builder::from_string(ui_src);
+
+    // Get window
+    let window = builder.object::<ApplicationWindow>("ConflictsWindow").unwrap();
+    window.set_transient_for(Some(parent_window));
+
+    // Find conflicts between enabled mods
+    let conflict_index = ConflictIndex::build(&model.items());
+
+    // Set summary
+    let summary = builder.object::<Label>("ConflictsSummary").unwrap();
+
+    summary.set_label(&match conflict_index.conflicts.len() {
+        0 => "No files are modified by more than one enabled mod.".to_owned(),
+        1 => "1 file is modified by more than one enabled mod.".to_owned(),
+        n => format!("{} files are modified by more than one enabled mod.", n)
+    });
+
+    // Add conflicts to list
+    let conflict_list = builder.object::<ListBox>("ConflictList").unwrap();
+
+    for conflict in &conflict_index.conflicts {
+        conflict_list.append(&create_conflict_row(conflict));
+    }
+
+    window
+}
+
+// Create a list row for a contested file
+fn create_conflict_row(conflict: &Conflict) -> Box {
+    let row = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+
+    // Add file path
+    let path = Label::builder()
+        .label(&conflict.path)
+        .halign(Align::Start)
+        .ellipsize(EllipsizeMode::Middle)
+        .tooltip_text(&conflict.path)
+        .build();
+    path.add_css_class("heading");
+    row.append(&path);
+
+    // Add mods modifying the file
+    let winner = conflict.winner();
+
+    for conflicting_mod in &conflict.mods {
+        // Mark the mod whose version is loaded
+        let marker = if winner.is_some_and(|w| w.filename == conflicting_mod.filename) {
+            "<span foreground='greenyellow' weight='bold'>✓</span>"
+        }
+        else {
+            "•"
+        };
+
+        let markup = format!(
+            "{} {} <span alpha='60%'>(load priority {})</span>",
+            marker,
+            glib::markup_escape_text(&conflicting_mod.filename),
+            conflicting_mod.load_priority
+        );
+
+        let label = Label::builder()
+            .use_markup(true)
+            .label(&markup)
+            .halign(Align::Start)
+            .margin_start(10)
+            .ellipsize(EllipsizeMode::End)
+            .build();
+        row.append(&label);
+    }
+
+    // Explain why the winner can't be determined
+    if winner.is_none() {
+        let label = Label::builder()
+            .use_markup(true)
+            .label(
+                "<span foreground='orange'>These mods have the same load priority, so the loaded version of \
+                 this file is undetermined.</span>"
+            )
+            .halign(Align::Start)
+            .margin_start(10)
+            .wrap(true)
+            .xalign(0.0)
+            .build();
+        row.append(&label);
+    }
+
+    row
+}
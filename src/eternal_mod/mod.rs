@@ -0,0 +1,243 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::Path
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use zip::{result::ZipResult, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+// UTF-8 byte order mark
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// Warning for EternalMod.json files with a byte order mark, which are still read fully
+const BOM_WARNING: &str = "EternalMod.json starts with a byte order mark (BOM).";
+
+// Contents of a mod's EternalMod.json
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EternalMod {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_priority: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_version: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>
+}
+
+impl EternalMod {
+    // Parse EternalMod.json, collecting warnings for any problems found
+    pub fn from_slice(data: &[u8]) -> (EternalMod, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        // Strip byte order mark
+        let data = match data.strip_prefix(UTF8_BOM) {
+            Some(d) => {
+                warnings.push(BOM_WARNING.to_owned());
+                d
+            },
+            None => data
+        };
+
+        // Parse JSON
+        let mut json = match serde_json::from_slice::<Value>(data) {
+            Ok(Value::Object(o)) => o,
+            Ok(_) => {
+                warnings.push("EternalMod.json is not a JSON object.".to_owned());
+                return (EternalMod::default(), warnings);
+            },
+            Err(e) => {
+                warnings.push(format!("EternalMod.json is not valid JSON: {}.", e));
+                return (EternalMod::default(), warnings);
+            }
+        };
+
+        // Set aside fields with the wrong type
+        let mut invalid_fields = Map::new();
+
+        for (key, expected) in [
+            ("name", "a string"),
+            ("author", "a string"),
+            ("description", "a string"),
+            ("version", "a string"),
+            ("loadPriority", "an integer"),
+            ("requiredVersion", "an integer")
+        ] {
+            let is_valid = match json.get(key) {
+                None | Some(Value::Null) => true,
+                Some(Value::String(_)) => expected == "a string",
+                Some(v) => expected == "an integer" && v.is_i64()
+            };
+
+            if !is_valid {
+                warnings.push(format!("\"{}\" in EternalMod.json should be {}.", key, expected));
+                invalid_fields.insert(key.to_owned(), json.remove(key).unwrap());
+            }
+        }
+
+        // Deserialize into struct
+        let mut eternal_mod: EternalMod = serde_json::from_value(Value::Object(json)).unwrap_or_default();
+
+        // Keep invalid fields as-is so they aren't lost when writing the file back
+        eternal_mod.extra.append(&mut invalid_fields);
+
+        (eternal_mod, warnings)
+    }
+}
+
+// Read a mod's EternalMod.json, ignoring warnings
+pub fn read_eternal_mod(mod_path: &Path) -> ZipResult<EternalMod> {
+    Ok(read_from_zip(&mut ZipArchive::new(File::open(mod_path)?)?)?.0)
+}
+
+// Read EternalMod.json and its warnings from an opened mod zip
+fn read_from_zip(mod_zip: &mut ZipArchive<File>) -> ZipResult<(EternalMod, Vec<String>)> {
+    match mod_zip.by_name("EternalMod.json") {
+        Ok(mut eternal_mod_file) => {
+            let mut buffer = Vec::new();
+            eternal_mod_file.read_to_end(&mut buffer)?;
+            Ok(EternalMod::from_slice(&buffer))
+        },
+        Err(_) => Ok((EternalMod::default(), Vec::new()))
+    }
+}
+
+// Set the load priority in a mod's EternalMod.json, creating the file if needed
+pub fn write_load_priority(mod_path: &Path, load_priority: i64) -> io::Result<()> {
+    let mut mod_zip = ZipArchive::new(File::open(mod_path)?)?;
+
+    // Read current EternalMod.json
+    let (mut eternal_mod, warnings) = read_from_zip(&mut mod_zip)?;
+
+    // Don't overwrite an EternalMod.json that wasn't read fully
+    if let Some(warning) = warnings.into_iter().find(|w| w != BOM_WARNING) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} Fix it to change the mod's load priority.", warning)
+        ));
+    }
+
+    eternal_mod.load_priority = Some(load_priority);
+
+    // Write new zip to a hidden temporary file next to the mod
+    let temp_path = mod_path.with_file_name(format!(
+        ".{}.tmp",
+        mod_path.file_name().unwrap().to_string_lossy()
+    ));
+
+    let result = write_mod_zip(&mut mod_zip, &eternal_mod, &temp_path);
+    drop(mod_zip);
+
+    // Replace the mod with the new zip
+    match result
+        .map_err(io::Error::from)
+        .and_then(|_| fs::rename(&temp_path, mod_path))
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+// Copy a mod's zip entries to a new file, replacing its EternalMod.json
+fn write_mod_zip(mod_zip: &mut ZipArchive<File>, eternal_mod: &EternalMod, path: &Path) -> ZipResult<()> {
+    let mut writer = ZipWriter::new(File::create(path)?);
+
+    // Copy entries without recompressing them
+    for i in 0..mod_zip.len() {
+        let entry = mod_zip.by_index_raw(i)?;
+
+        if entry.name() == "EternalMod.json" {
+            continue;
+        }
+
+        writer.raw_copy_file(entry)?;
+    }
+
+    // Write new EternalMod.json
+    let json = serde_json::to_vec_pretty(eternal_mod).map_err(io::Error::from)?;
+    writer.start_file(
+        "EternalMod.json",
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+    )?;
+    writer.write_all(&json)?;
+
+    // Make sure the file is written to disk before renaming it
+    writer.finish()?.sync_all()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf, process};
+
+    use super::*;
+
+    // Write a mod zip with the given EternalMod.json to a temporary file
+    fn write_test_mod(name: &str, eternal_mod_json: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("eternal-mod-test-{}-{}.zip", process::id(), name));
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+
+        writer
+            .start_file("EternalMod.json", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(eternal_mod_json).unwrap();
+        writer
+            .start_file("gameresources/file.decl", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"decl").unwrap();
+        writer.finish().unwrap();
+
+        path
+    }
+
+    #[test]
+    fn load_priority_keeps_other_fields() {
+        let path = write_test_mod(
+            "valid",
+            b"\xEF\xBB\xBF{\"name\": \"Mod\", \"author\": \"Someone\", \"loadPriority\": 3}"
+        );
+
+        write_load_priority(&path, -1).unwrap();
+        let eternal_mod = read_eternal_mod(&path).unwrap();
+        let mut mod_zip = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let has_decl = mod_zip.by_name("gameresources/file.decl").is_ok();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(eternal_mod.load_priority, Some(-1));
+        assert_eq!(eternal_mod.name.as_deref(), Some("Mod"));
+        assert_eq!(eternal_mod.author.as_deref(), Some("Someone"));
+        assert!(has_decl);
+    }
+
+    #[test]
+    fn load_priority_is_not_written_to_unreadable_files() {
+        for (name, json) in [
+            ("invalid", &b"{\"name\": \"Mod\","[..]),
+            ("not-object", b"[\"Mod\"]"),
+            ("wrong-type", b"{\"loadPriority\": \"1\"}")
+        ] {
+            let path = write_test_mod(name, json);
+            let before = fs::read(&path).unwrap();
+
+            let result = write_load_priority(&path, 1);
+            let after = fs::read(&path).unwrap();
+            let _ = fs::remove_file(&path);
+
+            assert!(result.is_err(), "{}", name);
+            assert_eq!(before, after, "{}", name);
+        }
+    }
+}
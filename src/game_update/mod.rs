@@ -0,0 +1,75 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use crate::{config, hash, steam};
+
+// Game files that change when DOOM Eternal is updated
+const FINGERPRINT_FILES: [&str; 2] = ["DOOMEternalx64vk.exe", "base/packagemapspec.json"];
+
+// Get the paths of the fingerprinted game files
+pub fn fingerprint_files() -> Vec<PathBuf> {
+    let game_path = crate::game_path();
+
+    FINGERPRINT_FILES.iter().map(|f| game_path.join(f)).collect()
+}
+
+// Get the fingerprint of the installed game version
+// The mod injector's backups are used when they exist, since it modifies the files
+fn fingerprint() -> Option<String> {
+    let mut parts = fingerprint_files()
+        .into_iter()
+        .map(|f| {
+            let mut backup: OsString = f.clone().into_os_string();
+            backup.push(".backup");
+            let backup = PathBuf::from(backup);
+
+            hash::hash_file(if backup.is_file() { &backup } else { &f }).ok()
+        })
+        .collect::<Option<Vec<String>>>()?;
+
+    // Steam's build ID changes even when the backups hide an update
+    if let Some(build_id) = steam::build_id(&crate::game_path()) {
+        parts.push(build_id);
+    }
+
+    Some(parts.join(":"))
+}
+
+// Get the fingerprint stored in the config file for the current game folder
+fn stored_fingerprint() -> Option<String> {
+    config::load_user()
+        .string("game-fingerprints", &config::install_id(&crate::game_path()))
+        .ok()
+        .map(|f| f.to_string())
+}
+
+// Store the fingerprint of the current game files in the config file
+pub fn record() -> bool {
+    let fingerprint = match fingerprint() {
+        Some(f) => f,
+        None => return false
+    };
+
+    // Load config file, keeping other settings
+    let keyfile = config::load_user();
+    keyfile.set_string(
+        "game-fingerprints",
+        &config::install_id(&crate::game_path()),
+        &fingerprint
+    );
+
+    config::save(&keyfile)
+}
+
+// Check if the game files changed since the fingerprint was stored
+// The first fingerprint for a game folder is stored without reporting a change
+pub fn has_changed() -> bool {
+    let stored = match stored_fingerprint() {
+        Some(f) => f,
+        None => {
+            record();
+            return false;
+        }
+    };
+
+    fingerprint().is_some_and(|f| f != stored)
+}
@@ -0,0 +1,12 @@
+use std::{fs::File, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+// Get the SHA-256 hash of a file as a hex string
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
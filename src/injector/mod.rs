@@ -1,116 +1,174 @@
-use std::process::{Command, Stdio};
+#[cfg(target_os = "linux")]
+pub mod progress;
 
+use std::{fs, io, path::PathBuf, process::Command};
 #[cfg(target_os = "linux")]
-// Check if program exists on Linux
-fn linux_program_exists(program: &str) -> bool {
-    // Use "command -v" to check
-    match Command::new("sh")
-        .args(["-c", &format!("command -v {}", program)])
-        .stdout(Stdio::null())
-        .status()
-    {
-        Ok(c) => c.code().unwrap_or(1) == 0,
-        Err(_) => false
-    }
-}
+use std::{
+    io::{Read, Write},
+    sync::mpsc
+};
 
 #[cfg(target_os = "linux")]
-// Get sensible terminal on Linux
-fn get_terminal_linux() -> Option<String> {
-    use std::env;
-
-    // List of common terminals from i3-sensible-terminal
-    let mut terminals: Vec<String> = vec![
-        "x-terminal-emulator".into(),
-        "mate-terminal".into(),
-        "gnome-terminal".into(),
-        "terminator".into(),
-        "xfce4-terminal".into(),
-        "urxvt".into(),
-        "rxvt".into(),
-        "termit".into(),
-        "Eterm".into(),
-        "aterm".into(),
-        "uxterm".into(),
-        "xterm".into(),
-        "roxterm".into(),
-        "termite".into(),
-        "lxterminal".into(),
-        "terminology".into(),
-        "foot".into(),
-        "st".into(),
-        "qterminal".into(),
-        "lilyterm".into(),
-        "tilix".into(),
-        "terminix".into(),
-        "konsole".into(),
-        "kitty".into(),
-        "guake".into(),
-        "tilda".into(),
-        "alacritty".into(),
-        "hyper".into(),
-    ];
-
-    // Allow user to specify their own terminal
-    if let Ok(terminal) = env::var("TERMINAL") {
-        terminals.insert(0, terminal);
-    }
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 
-    // Get terminal
-    terminals.into_iter().find(|t| linux_program_exists(t))
+#[cfg(target_os = "linux")]
+// Event sent while the mod injector runs
+pub enum InjectorEvent {
+    Output(String),
+    Exited(Option<u32>)
 }
 
 #[cfg(target_os = "linux")]
-// Run mod injector on terminal window on Linux
-pub fn run() -> bool {
-    use std::fs::File;
+// Mod injector running under a pseudo-terminal
+pub struct InjectorProcess {
+    writer: Box<dyn Write + Send>
+}
 
-    // Get sensible terminal
-    let terminal = match get_terminal_linux() {
-        Some(t) => t,
-        None => return false
-    };
+#[cfg(target_os = "linux")]
+impl InjectorProcess {
+    // Send a line of input to the injector's prompts
+    pub fn write_line(&mut self, input: &str) -> io::Result<()> {
+        self.writer.write_all(format!("{}\r", input).as_bytes())?;
+        self.writer.flush()
+    }
+}
 
-    // Get argument to run commands in terminal
-    let term_arg = match terminal.as_str() {
-        "gnome-terminal" => "--",
-        "tilda" => "-c",
-        _ => "-e"
-    };
+#[cfg(target_os = "linux")]
+// Run mod injector under a pseudo-terminal on Linux, sending its output through the channel
+pub fn spawn(tx: mpsc::Sender<InjectorEvent>) -> io::Result<InjectorProcess> {
+    use std::{fs::File, thread, time::Duration};
+
+    // Open pseudo-terminal
+    let pty = native_pty_system()
+        .openpty(PtySize {
+            rows: 40,
+            cols: 120,
+            pixel_width: 0,
+            pixel_height: 0
+        })
+        .map_err(|e| io::Error::other(e.to_string()))?;
 
     // Create file to tell the injector we're running from the manager
-    let f = File::create(crate::GAME_PATH.get().unwrap().join("ETERNALMODMANAGER"));
+    let f = File::create(crate::game_path().join("ETERNALMODMANAGER"));
     drop(f);
 
-    // Get injector path
-    let injector_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjectorShell.sh")
-        .into_os_string()
-        .into_string()
-        .unwrap();
-
     // Run injector
-    let _ = Command::new(terminal)
-        .args([term_arg, &injector_path])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .stdin(Stdio::null())
-        .status();
+    let mut command = CommandBuilder::new(injector_script_path());
+    command.cwd(crate::game_path());
+    command.env("TERM", "xterm");
 
-    true
+    let mut child = pty
+        .slave
+        .spawn_command(command)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    // Close our copy of the slave so reads end when the injector exits
+    drop(pty.slave);
+
+    let mut reader = pty
+        .master
+        .try_clone_reader()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let writer = pty
+        .master
+        .take_writer()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    // Read output
+    let (done_tx, done_rx) = mpsc::channel();
+    let output_tx = tx.clone();
+
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        let mut pending = Vec::new();
+
+        while let Ok(read) = reader.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+
+            pending.extend_from_slice(&buffer[..read]);
+
+            // Keep incomplete UTF-8 sequences for the next read
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(s) => s.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => pending.len()
+            };
+
+            let output = String::from_utf8_lossy(&pending[..valid]).into_owned();
+            pending.drain(..valid);
+
+            if output_tx
+                .send(InjectorEvent::Output(strip_escape_codes(&output)))
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        let _ = done_tx.send(());
+    });
+
+    // Wait for injector
+    thread::spawn(move || {
+        let status = child.wait().ok().map(|s| s.exit_code());
+
+        // Give the reader some time to send the remaining output,
+        // the game could still be holding the terminal open
+        let _ = done_rx.recv_timeout(Duration::from_millis(500));
+        drop(pty.master);
+
+        let _ = tx.send(InjectorEvent::Exited(status));
+    });
+
+    Ok(InjectorProcess { writer })
+}
+
+#[cfg(target_os = "linux")]
+// Remove terminal escape sequences and carriage returns from output
+fn strip_escape_codes(output: &str) -> String {
+    let mut stripped = String::with_capacity(output.len());
+    let mut chars = output.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                // Skip control sequence
+                if chars.next_if_eq(&'[').is_some() {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                else if chars.next_if_eq(&']').is_some() {
+                    // Skip operating system command
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                else {
+                    chars.next();
+                }
+            },
+            '\r' => {},
+            c => stripped.push(c)
+        }
+    }
+
+    stripped
 }
 
 #[cfg(target_os = "windows")]
-// Run mod injector on terminal window on Windows
+// Run mod injector on terminal window on Windows, waiting for the window to be closed
 pub fn run() -> bool {
-    use std::os::windows::process::CommandExt;
+    use std::{os::windows::process::CommandExt, process::Stdio};
 
     // Get injector path
-    let injector_path = crate::GAME_PATH
-        .get()
-        .unwrap()
+    let injector_path = crate::game_path()
         .join("EternalModInjector.bat")
         .into_os_string()
         .into_string()
@@ -118,8 +176,8 @@ pub fn run() -> bool {
 
     // Run injector
     let _ = Command::new("cmd.exe")
-        .raw_arg(format!("/c start cmd.exe /c \"{}\"", injector_path))
-        .current_dir(crate::GAME_PATH.get().unwrap())
+        .raw_arg(format!("/c start \"\" /wait cmd.exe /c \"{}\"", injector_path))
+        .current_dir(crate::game_path())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .stdin(Stdio::null())
@@ -127,3 +185,141 @@ pub fn run() -> bool {
 
     true
 }
+
+// Run mod injector attached to the current terminal, returning its exit code
+pub fn run_attached() -> io::Result<Option<i32>> {
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        // Create file to tell the injector we're running from the manager
+        let f = fs::File::create(crate::game_path().join("ETERNALMODMANAGER"));
+        drop(f);
+
+        Command::new(injector_script_path())
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+
+        let mut command = Command::new("cmd.exe");
+        command.raw_arg(format!("/c \"{}\"", injector_script_path().display()));
+        command
+    };
+
+    // Run injector, inheriting our stdio
+    let status = command.current_dir(crate::game_path()).status()?;
+
+    Ok(status.code())
+}
+
+// Get the path of the mod injector script
+fn injector_script_path() -> PathBuf {
+    #[cfg(target_os = "linux")]
+    let script_name = "EternalModInjectorShell.sh";
+
+    #[cfg(target_os = "windows")]
+    let script_name = "EternalModInjector.bat";
+
+    crate::game_path().join(script_name)
+}
+
+// Variable holding the mod loader version in the injector script
+const LOADER_VERSION_KEY: &str = "ModLoaderVersion";
+
+// Parse the mod loader version assignment, such as "set ModLoaderVersion=20" or "ModLoaderVersion=\"20\""
+fn parse_loader_version(line: &str) -> Option<u32> {
+    // Remove shell and batch keywords
+    let mut line = line.trim().trim_start_matches('"');
+
+    for prefix in ["set ", "export ", "declare ", "readonly ", "local "] {
+        if line
+            .get(..prefix.len())
+            .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        {
+            line = line[prefix.len()..].trim_start().trim_start_matches('"');
+        }
+    }
+
+    // Split into key and value
+    let (key, value) = line.split_once('=')?;
+
+    // Other versions in the script aren't the mod loader's
+    if !key.trim().eq_ignore_ascii_case(LOADER_VERSION_KEY) {
+        return None;
+    }
+
+    // Get the leading number of the value
+    let digits = value
+        .trim()
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    digits.parse().ok()
+}
+
+// Detect the installed mod loader version from the injector script
+pub fn installed_version() -> Option<u32> {
+    let script = fs::read(injector_script_path()).ok()?;
+    let script = String::from_utf8_lossy(&script);
+
+    script.lines().find_map(parse_loader_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_loader_version;
+
+    #[test]
+    fn parses_shell_assignments() {
+        assert_eq!(parse_loader_version("ModLoaderVersion=20"), Some(20));
+        assert_eq!(parse_loader_version("  ModLoaderVersion = 21  "), Some(21));
+        assert_eq!(parse_loader_version("export ModLoaderVersion=\"22\""), Some(22));
+        assert_eq!(parse_loader_version("readonly ModLoaderVersion='23'"), Some(23));
+        assert_eq!(parse_loader_version("local modloaderversion=24"), Some(24));
+    }
+
+    #[test]
+    fn parses_batch_assignments() {
+        assert_eq!(parse_loader_version("set ModLoaderVersion=20"), Some(20));
+        assert_eq!(parse_loader_version("SET \"ModLoaderVersion=21\""), Some(21));
+        assert_eq!(parse_loader_version("\"set ModLoaderVersion=22\""), Some(22));
+    }
+
+    #[test]
+    fn ignores_other_variables() {
+        assert_eq!(parse_loader_version("OldModLoaderVersion=19"), None);
+        assert_eq!(parse_loader_version("ModLoaderVersionUrl=20"), None);
+        assert_eq!(parse_loader_version("set ToolsVersion=6"), None);
+        assert_eq!(
+            parse_loader_version("echo \"ModLoaderVersion=$ModLoaderVersion\""),
+            None
+        );
+        assert_eq!(parse_loader_version("# ModLoaderVersion is set below"), None);
+    }
+
+    #[test]
+    fn ignores_garbage_values() {
+        assert_eq!(parse_loader_version("ModLoaderVersion="), None);
+        assert_eq!(parse_loader_version("ModLoaderVersion=abc"), None);
+        assert_eq!(parse_loader_version("ModLoaderVersion=\"\""), None);
+        assert_eq!(parse_loader_version("ModLoaderVersion=99999999999"), None);
+        assert_eq!(parse_loader_version("ModLoaderVersion=20b"), Some(20));
+        assert_eq!(parse_loader_version(""), None);
+    }
+
+    #[test]
+    fn finds_the_version_in_a_script() {
+        let script = [
+            "#!/bin/bash",
+            "ToolsVersion=6",
+            "echo \"ModLoaderVersion=old\"",
+            "export ModLoaderVersion=20",
+            "ModLoaderVersion=21"
+        ]
+        .join("\n");
+
+        assert_eq!(script.lines().find_map(parse_loader_version), Some(20));
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::BTreeSet;
+
+// Known failure messages and the advice shown for them
+// A line matches if it contains the keyword and any of the qualifiers, if there are any
+static KNOWN_FAILURES: [(&str, &[&str], &str); 6] = [
+    (
+        "doometernalx64vk.exe",
+        &["not found", "could not find", "couldn't find", "missing"],
+        "The injector couldn't find the game executable. Make sure the modding tools were extracted into \
+         the game folder and that the game is fully installed."
+    ),
+    (
+        "backup",
+        &["mismatch", "outdated", "don't match", "do not match", "corrupt"],
+        "The game files don't match the injector's backups, usually because the game was updated. Enable \
+         \"Reset backups\" in the advanced options, verify the game files in Steam, then run the injector \
+         again."
+    ),
+    (
+        "packagemapspec",
+        &[
+            "not found",
+            "could not",
+            "couldn't",
+            "missing",
+            "invalid",
+            "failed"
+        ],
+        "The game's packagemapspec.json file is missing or damaged. Verify the game files in Steam, then \
+         run the injector again."
+    ),
+    (
+        "permission denied",
+        &[],
+        "The injector doesn't have permission to modify the game files. Make sure the game folder is \
+         writable by your user and that the modding tools are executable."
+    ),
+    (
+        "no space left",
+        &[],
+        "There isn't enough free disk space to inject the mods. Free up some space and try again."
+    ),
+    (
+        "command not found",
+        &[],
+        "A program needed by the injector is missing. Install the dependencies listed in the modding tools' \
+         readme and try again."
+    )
+];
+
+// Get the resources modified by the given mod entries, named after their first folder
+// Entries in streamdb are injected into the stream database instead
+pub fn resource_names<'a>(entries: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+    entries
+        .into_iter()
+        .filter_map(|e| e.split_once('/').map(|(c, _)| c.to_lowercase()))
+        .filter(|c| c != "streamdb")
+        .collect()
+}
+
+// Progress and messages parsed from the mod injector's output
+#[derive(Clone, Debug, Default)]
+pub struct InjectionProgress {
+    pub expected_resources: BTreeSet<String>,
+    pub processed_resources: BTreeSet<String>,
+    pub enabled_mods: Vec<String>,
+    pub loaded_mods: BTreeSet<String>,
+    pub skipped_mods: BTreeSet<String>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    pub advice: Vec<&'static str>,
+    partial_line: String
+}
+
+impl InjectionProgress {
+    // Create a parser expecting the given resources and mods
+    pub fn new(expected_resources: BTreeSet<String>, enabled_mods: Vec<String>) -> InjectionProgress {
+        InjectionProgress {
+            expected_resources,
+            enabled_mods,
+            ..Default::default()
+        }
+    }
+
+    // Parse a chunk of output, keeping incomplete lines for later
+    pub fn feed(&mut self, output: &str) {
+        self.partial_line.push_str(output);
+
+        while let Some(end) = self.partial_line.find('\n') {
+            let line = self.partial_line[..end].to_owned();
+            self.partial_line.drain(..=end);
+            self.parse_line(&line);
+        }
+    }
+
+    // Parse the output left after the injector exits
+    pub fn finish(&mut self) {
+        let line = std::mem::take(&mut self.partial_line);
+        self.parse_line(&line);
+    }
+
+    // Get the fraction of resources processed
+    pub fn fraction(&self) -> Option<f64> {
+        if self.expected_resources.is_empty() {
+            return None;
+        }
+
+        Some(self.processed_resources.len() as f64 / self.expected_resources.len() as f64)
+    }
+
+    // Get a summary of the injection for the user
+    pub fn summary(&self) -> String {
+        let mut summary = Vec::new();
+
+        if !self.expected_resources.is_empty() {
+            summary.push(format!(
+                "Resources processed: {} of {}.",
+                self.processed_resources.len(),
+                self.expected_resources.len()
+            ));
+        }
+
+        if !self.loaded_mods.is_empty() || !self.skipped_mods.is_empty() {
+            summary.push(format!(
+                "Mods loaded: {} of {}.",
+                self.loaded_mods.len(),
+                self.enabled_mods.len()
+            ));
+        }
+
+        if !self.skipped_mods.is_empty() {
+            summary.push(format!(
+                "Skipped mods:\n{}",
+                self.skipped_mods
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ));
+        }
+
+        if !self.warnings.is_empty() {
+            summary.push(format!("Warnings: {}.", self.warnings.len()));
+        }
+
+        if !self.errors.is_empty() {
+            let mut errors = self.errors.iter().take(5).cloned().collect::<Vec<String>>();
+
+            if self.errors.len() > 5 {
+                errors.push(format!("...and {} more.", self.errors.len() - 5));
+            }
+
+            summary.push(format!("Errors:\n{}", errors.join("\n")));
+        }
+
+        if !self.advice.is_empty() {
+            summary.push(self.advice.join("\n\n"));
+        }
+
+        summary.join("\n\n")
+    }
+
+    // Parse a single line of output
+    fn parse_line(&mut self, line: &str) {
+        let line = line.trim();
+
+        if line.is_empty() {
+            return;
+        }
+
+        let lowercase_line = line.to_lowercase();
+
+        // Find resources mentioned in the line
+        for word in lowercase_line.split(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            if self.expected_resources.contains(word) {
+                self.processed_resources.insert(word.to_owned());
+            }
+        }
+
+        // Find mods mentioned in the line
+        for mod_name in &self.enabled_mods {
+            if !lowercase_line.contains(&mod_name.to_lowercase()) {
+                continue;
+            }
+
+            if lowercase_line.contains("skip") || lowercase_line.contains("not online safe") {
+                self.loaded_mods.remove(mod_name);
+                self.skipped_mods.insert(mod_name.clone());
+            }
+            else if lowercase_line.contains("load") && !self.skipped_mods.contains(mod_name) {
+                self.loaded_mods.insert(mod_name.clone());
+            }
+        }
+
+        // Ignore mod names when looking for errors, since they can contain any word
+        let message = self.enabled_mods.iter().fold(lowercase_line, |message, m| {
+            message.replace(&m.to_lowercase(), "")
+        });
+
+        // Find errors and warnings
+        if message.starts_with("warning") {
+            self.warnings.push(line.to_owned());
+        }
+        else if message.starts_with("error") || message.contains("error:") || message.contains("failed") {
+            self.errors.push(line.to_owned());
+        }
+
+        // Find known failures
+        for advice in known_failure_advice(&message) {
+            if !self.advice.contains(&advice) {
+                self.advice.push(advice);
+            }
+        }
+    }
+}
+
+// Get advice for the known failures found in a lowercase message
+pub fn known_failure_advice(message: &str) -> impl Iterator<Item = &'static str> + '_ {
+    KNOWN_FAILURES
+        .iter()
+        .filter(move |(keyword, qualifiers, _)| {
+            message.contains(keyword)
+                && (qualifiers.is_empty() || qualifiers.iter().any(|q| message.contains(q)))
+        })
+        .map(|(_, _, advice)| *advice)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{resource_names, InjectionProgress};
+
+    // Output in the format printed by the mod injector and the mod loader
+    const OUTPUT: &str = "Checking resources...
+Backing up gameresources_patch1.resources...
+Backing up e1m1_intro_patch1.resources...
+Loading mods...
+Loading mod: ultrawide-hud.zip
+Loading mod: failed-weapons-fix.zip
+Skipping mod: unsafe-pvp.zip (not online safe)
+Replaced generated/decls/hud/hud.decl in gameresources_patch1.resources
+Number of files replaced: 12 in gameresources_patch1.resources
+WARNING: Unknown file type found in mod: readme.txt
+ERROR: Failed to open e1m1_intro_patch1.resources
+Finished.
+";
+
+    // Create a parser expecting the resources and mods of the sample output
+    fn parser() -> InjectionProgress {
+        InjectionProgress::new(
+            BTreeSet::from(["gameresources_patch1".to_owned(), "e1m1_intro_patch1".to_owned()]),
+            vec![
+                "ultrawide-hud.zip".to_owned(),
+                "failed-weapons-fix.zip".to_owned(),
+                "unsafe-pvp.zip".to_owned(),
+            ]
+        )
+    }
+
+    #[test]
+    fn gets_resources_from_first_folder() {
+        let entries = [
+            "gameresources_patch1/generated/decls/hud/hud.decl",
+            "E1M1_Intro_Patch1/maps/game/sp/e1m1_intro.entities",
+            "streamdb/textures/logo.tga",
+            "gameresources_patch1/generated/decls/weapon/shotgun.decl"
+        ];
+
+        assert_eq!(
+            resource_names(entries),
+            BTreeSet::from(["gameresources_patch1".to_owned(), "e1m1_intro_patch1".to_owned()])
+        );
+    }
+
+    #[test]
+    fn tracks_resources_and_mods() {
+        let mut progress = parser();
+        progress.feed(OUTPUT);
+        progress.finish();
+
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(
+            progress.loaded_mods,
+            BTreeSet::from([
+                "ultrawide-hud.zip".to_owned(),
+                "failed-weapons-fix.zip".to_owned()
+            ])
+        );
+        assert_eq!(
+            progress.skipped_mods,
+            BTreeSet::from(["unsafe-pvp.zip".to_owned()])
+        );
+    }
+
+    #[test]
+    fn keeps_lines_split_across_chunks() {
+        let mut progress = parser();
+        progress.feed("Backing up gameresou");
+        assert!(progress.processed_resources.is_empty());
+
+        progress.feed("rces_patch1.resources...\nLoading mod: ultraw");
+        progress.finish();
+        progress.feed("ide-hud.zip");
+
+        assert_eq!(progress.fraction(), Some(0.5));
+        assert!(progress.loaded_mods.is_empty());
+    }
+
+    #[test]
+    fn finds_errors_and_warnings() {
+        let mut progress = parser();
+        progress.feed(OUTPUT);
+
+        assert_eq!(
+            progress.warnings,
+            ["WARNING: Unknown file type found in mod: readme.txt"]
+        );
+        assert_eq!(
+            progress.errors,
+            ["ERROR: Failed to open e1m1_intro_patch1.resources"]
+        );
+    }
+
+    #[test]
+    fn ignores_failed_in_mod_names() {
+        let mut progress = parser();
+        progress.feed("Loading mod: failed-weapons-fix.zip\nReplaced 3 files from failed-weapons-fix.zip\n");
+
+        assert!(progress.errors.is_empty());
+        assert_eq!(
+            progress.loaded_mods,
+            BTreeSet::from(["failed-weapons-fix.zip".to_owned()])
+        );
+    }
+
+    #[test]
+    fn gives_advice_for_known_failures() {
+        let mut progress = parser();
+        progress.feed(
+            "Backup of gameresources_patch1.resources is outdated\nbash: 7z: command not found\nBackup of \
+             gameresources_patch1.resources is outdated\n"
+        );
+
+        assert_eq!(progress.advice.len(), 2);
+        assert_eq!(progress.errors.len(), 0);
+    }
+}
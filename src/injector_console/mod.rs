@@ -0,0 +1,292 @@
+#[cfg(target_os = "windows")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::{cell::RefCell, rc::Rc};
+use std::{
+    io,
+    sync::mpsc::{self, TryRecvError},
+    time::Duration
+};
+
+use adw::prelude::*;
+#[cfg(target_os = "linux")]
+use adw::AlertDialog;
+use gtk::{
+    glib::{self, clone, ControlFlow},
+    Builder, Button, Entry, Stack
+};
+#[cfg(target_os = "linux")]
+use gtk::{ApplicationWindow, Label, ProgressBar, TextView};
+
+#[cfg(target_os = "linux")]
+use crate::injector::{
+    progress::{self, InjectionProgress},
+    InjectorEvent, InjectorProcess
+};
+use crate::{injector, model::Model};
+
+// Panel showing the mod injector's output in the manager window
+#[derive(Clone)]
+pub struct InjectorConsole {
+    stack: Stack,
+    #[cfg(target_os = "linux")]
+    log: TextView,
+    input: Entry,
+    send_button: Button,
+    close_button: Button,
+    #[cfg(target_os = "linux")]
+    status: Label,
+    #[cfg(target_os = "linux")]
+    progress_bar: ProgressBar,
+    #[cfg(target_os = "linux")]
+    process: Rc<RefCell<Option<InjectorProcess>>>
+}
+
+impl InjectorConsole {
+    // Get the console widgets from the manager window's builder
+    pub fn new(builder: &Builder) -> InjectorConsole {
+        let console = InjectorConsole {
+            stack: builder.object::<Stack>("InfoStack").unwrap(),
+            #[cfg(target_os = "linux")]
+            log: builder.object::<TextView>("InjectorConsoleLog").unwrap(),
+            input: builder.object::<Entry>("InjectorConsoleInput").unwrap(),
+            send_button: builder.object::<Button>("InjectorConsoleSend").unwrap(),
+            close_button: builder.object::<Button>("InjectorConsoleClose").unwrap(),
+            #[cfg(target_os = "linux")]
+            status: builder.object::<Label>("InjectorConsoleStatus").unwrap(),
+            #[cfg(target_os = "linux")]
+            progress_bar: builder.object::<ProgressBar>("InjectorProgress").unwrap(),
+            #[cfg(target_os = "linux")]
+            process: Rc::new(RefCell::new(None))
+        };
+
+        // Send input on enter or button click
+        console.input.connect_activate(clone!(
+            #[strong]
+            console,
+            move |_| {
+                console.send_input();
+            }
+        ));
+
+        console.send_button.connect_clicked(clone!(
+            #[strong]
+            console,
+            move |_| {
+                console.send_input();
+            }
+        ));
+
+        // Go back to the mod information
+        console.close_button.connect_clicked(clone!(
+            #[strong]
+            console,
+            move |_| {
+                console.stack.set_visible_child_name("info");
+            }
+        ));
+
+        console.set_running(false);
+        console
+    }
+
+    // Check if the injector is running
+    pub fn is_running(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        let running = self.process.borrow().is_some();
+
+        #[cfg(target_os = "windows")]
+        let running = false;
+
+        running
+    }
+
+    // Toggle the widgets that only work while the injector runs
+    fn set_running(&self, running: bool) {
+        self.input.set_sensitive(running);
+        self.send_button.set_sensitive(running);
+        self.close_button.set_sensitive(!running);
+    }
+
+    #[cfg(target_os = "linux")]
+    // Append output to the log and scroll to the end
+    fn append_output(&self, output: &str) {
+        let buffer = self.log.buffer();
+        buffer.insert(&mut buffer.end_iter(), output);
+        buffer.place_cursor(&buffer.end_iter());
+        self.log
+            .scroll_to_mark(&buffer.get_insert(), 0.0, false, 0.0, 1.0);
+    }
+
+    // Send the input entry's text to the injector
+    fn send_input(&self) {
+        #[cfg(target_os = "linux")]
+        if let Some(process) = self.process.borrow_mut().as_mut() {
+            if process.write_line(&self.input.text()).is_err() {
+                self.append_output("\n[Failed to send input to the mod injector]\n");
+            }
+        }
+
+        self.input.set_text("");
+    }
+
+    #[cfg(target_os = "linux")]
+    // Run the mod injector, calling on_exit with its exit code when it finishes
+    pub fn run(&self, model: &Model, on_exit: impl FnOnce(Option<u32>) + 'static) -> io::Result<()> {
+        let (tx, rx) = mpsc::channel();
+
+        // Get the resources and mods the injector should process
+        let enabled_mods = model
+            .items()
+            .into_iter()
+            .filter(|m| m.is_enabled() && m.is_valid())
+            .collect::<Vec<_>>();
+
+        let entries = enabled_mods
+            .iter()
+            .flat_map(|m| m.entries())
+            .collect::<Vec<String>>();
+        let expected_resources = progress::resource_names(entries.iter().map(String::as_str));
+
+        let mut progress = InjectionProgress::new(
+            expected_resources,
+            enabled_mods.iter().map(|m| m.filename().unwrap()).collect()
+        );
+
+        // Start injector
+        let process = injector::spawn(tx)?;
+        self.process.replace(Some(process));
+
+        // Show console
+        self.log.buffer().set_text("");
+        self.status.set_text("Running...");
+        self.set_running(true);
+        self.update_progress(&progress);
+        self.stack.set_visible_child_name("console");
+        self.input.grab_focus();
+
+        // Poll injector events
+        let console = self.clone();
+        let mut on_exit = Some(on_exit);
+
+        glib::timeout_add_local(Duration::from_millis(50), move || {
+            loop {
+                let exit_code = match rx.try_recv() {
+                    Ok(InjectorEvent::Output(output)) => {
+                        console.append_output(&output);
+                        progress.feed(&output);
+                        console.update_progress(&progress);
+                        continue;
+                    },
+                    Ok(InjectorEvent::Exited(code)) => code,
+                    Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => None
+                };
+
+                // Show exit status
+                progress.finish();
+                console.process.replace(None);
+                console.set_running(false);
+
+                let status = match exit_code {
+                    Some(0) => String::from("Finished successfully."),
+                    Some(code) => format!("Failed with exit code {}.", code),
+                    None => String::from("Exit status unknown.")
+                };
+
+                console.status.set_text(&status);
+                console.append_output(&format!("\n[{}]\n", status));
+
+                if exit_code == Some(0) {
+                    console.progress_bar.set_fraction(1.0);
+                }
+
+                if let Some(on_exit) = on_exit.take() {
+                    on_exit(exit_code);
+                }
+
+                console.show_summary(exit_code, &progress);
+                return ControlFlow::Break;
+            }
+        });
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    // Show the injection progress in the progress bar
+    fn update_progress(&self, progress: &InjectionProgress) {
+        match progress.fraction() {
+            Some(fraction) => {
+                self.progress_bar.set_fraction(fraction.min(1.0));
+                self.progress_bar.set_text(Some(&format!(
+                    "{} of {} resources processed",
+                    progress.processed_resources.len(),
+                    progress.expected_resources.len()
+                )));
+            },
+            None => {
+                self.progress_bar.pulse();
+                self.progress_bar.set_text(None);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    // Show a summary of the injection
+    fn show_summary(&self, exit_code: Option<u32>, progress: &InjectionProgress) {
+        let window = match self.stack.root().and_downcast::<ApplicationWindow>() {
+            Some(w) => w,
+            None => return
+        };
+
+        let heading = match exit_code {
+            Some(0) if progress.errors.is_empty() => String::from("Mods injected successfully."),
+            Some(0) => String::from("The mod injector finished with errors."),
+            Some(code) => format!("The mod injector failed with exit code {}.", code),
+            None => String::from("The mod injector stopped unexpectedly.")
+        };
+
+        let mut body = progress.summary();
+
+        if body.is_empty() {
+            body = String::from("Check the injector output for more details.");
+        }
+
+        let dialog = AlertDialog::builder()
+            .heading(heading)
+            .body(body)
+            .default_response("ok")
+            .close_response("ok")
+            .build();
+
+        dialog.add_responses(&[("ok", "_Ok")]);
+        dialog.present(Some(&window));
+    }
+
+    #[cfg(target_os = "windows")]
+    // Run the mod injector in its own console window, calling on_exit once it's closed
+    pub fn run(&self, _: &Model, on_exit: impl FnOnce(Option<u32>) + 'static) -> io::Result<()> {
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            tx.send(injector::run()).unwrap();
+        });
+
+        // Wait for the console window without blocking the UI
+        let mut on_exit = Some(on_exit);
+
+        glib::timeout_add_local(Duration::from_millis(250), move || match rx.try_recv() {
+            Err(TryRecvError::Empty) => ControlFlow::Continue,
+            _ => {
+                if let Some(on_exit) = on_exit.take() {
+                    on_exit(None);
+                }
+
+                ControlFlow::Break
+            }
+        });
+
+        Ok(())
+    }
+}
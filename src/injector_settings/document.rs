@@ -0,0 +1,225 @@
+use std::fmt;
+
+// Line of the injector settings file
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Line {
+    // ":KEY=VALUE" line, with the value kept as written
+    Setting { key: String, value: String },
+    // Any other line, such as comments, blank lines and the injector's file list
+    Other(String)
+}
+
+// Injector settings file that can be modified without changing the lines that weren't touched
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsDocument {
+    lines: Vec<Line>,
+    line_ending: &'static str,
+    trailing_newline: bool
+}
+
+impl SettingsDocument {
+    // Parse the contents of a settings file
+    pub fn parse(text: &str) -> SettingsDocument {
+        let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_newline = text.ends_with('\n');
+
+        let body = text.strip_suffix('\n').unwrap_or(text);
+        let body = body.strip_suffix('\r').unwrap_or(body);
+
+        let lines = if text.is_empty() {
+            Vec::new()
+        }
+        else {
+            body.split(line_ending)
+                .map(
+                    |line| match line.strip_prefix(':').and_then(|l| l.split_once('=')) {
+                        Some((key, value)) => Line::Setting {
+                            key: key.to_owned(),
+                            value: value.to_owned()
+                        },
+                        None => Line::Other(line.to_owned())
+                    }
+                )
+                .collect()
+        };
+
+        SettingsDocument {
+            lines,
+            line_ending,
+            trailing_newline
+        }
+    }
+
+    // Get a setting's value, without surrounding whitespace
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.settings().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    // Get all settings in file order
+    pub fn settings(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Setting { key, value } => Some((key.as_str(), value.trim())),
+            Line::Other(_) => None
+        })
+    }
+
+    // Set a setting's value, adding it after the last setting if it's not in the file
+    pub fn set(&mut self, key: &str, value: &str) {
+        let existing = self.lines.iter_mut().find_map(|line| match line {
+            Line::Setting { key: k, value } if k == key => Some(value),
+            _ => None
+        });
+
+        if let Some(existing) = existing {
+            // Keep the line as-is if the value didn't change
+            if existing.trim() != value {
+                *existing = value.to_owned();
+            }
+
+            return;
+        }
+
+        let position = self
+            .lines
+            .iter()
+            .rposition(|line| matches!(line, Line::Setting { .. }))
+            .map_or(0, |i| i + 1);
+
+        self.lines.insert(
+            position,
+            Line::Setting {
+                key: key.to_owned(),
+                value: value.to_owned()
+            }
+        );
+
+        if self.lines.len() == 1 {
+            self.trailing_newline = true;
+        }
+    }
+}
+
+impl fmt::Display for SettingsDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.line_ending)?;
+            }
+
+            match line {
+                Line::Setting { key, value } => write!(f, ":{}={}", key, value)?,
+                Line::Other(line) => f.write_str(line)?
+            }
+        }
+
+        if self.trailing_newline {
+            f.write_str(self.line_ending)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SettingsDocument;
+
+    const SETTINGS: &str = ":ONLINE_SAFE=1
+:AUTO_LAUNCH_GAME=0
+:GAME_PARAMETERS=+com_skipIntroVideo 1 +r_mode=5
+:HAS_CHECKED_RESOURCES=1
+
+gameresources.resources
+warehouse.resources
+";
+
+    #[test]
+    fn round_trips_unchanged() {
+        for text in [
+            SETTINGS,
+            &SETTINGS.replace('\n', "\r\n"),
+            SETTINGS.trim_end(),
+            "",
+            "\n",
+            "# comment\n\n:VERBOSE=0\n\n\n"
+        ] {
+            assert_eq!(SettingsDocument::parse(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn keeps_values_containing_equals() {
+        let settings = SettingsDocument::parse(SETTINGS);
+
+        assert_eq!(
+            settings.get("GAME_PARAMETERS"),
+            Some("+com_skipIntroVideo 1 +r_mode=5")
+        );
+    }
+
+    #[test]
+    fn missing_setting_is_none() {
+        let settings = SettingsDocument::parse(":VERBOSE=1\n");
+
+        assert_eq!(settings.get("GAME_PARAMETERS"), None);
+        assert_eq!(settings.get("VERBOSE"), Some("1"));
+    }
+
+    #[test]
+    fn set_replaces_in_place() {
+        let mut settings = SettingsDocument::parse(SETTINGS);
+        settings.set("AUTO_LAUNCH_GAME", "1");
+        settings.set("ONLINE_SAFE", "1");
+
+        assert_eq!(
+            settings.to_string(),
+            SETTINGS.replace(":AUTO_LAUNCH_GAME=0", ":AUTO_LAUNCH_GAME=1")
+        );
+    }
+
+    #[test]
+    fn set_keeps_unchanged_whitespace() {
+        let text = ":VERBOSE=1 \n";
+        let mut settings = SettingsDocument::parse(text);
+        settings.set("VERBOSE", "1");
+
+        assert_eq!(settings.to_string(), text);
+    }
+
+    #[test]
+    fn set_adds_after_last_setting() {
+        let mut settings = SettingsDocument::parse(SETTINGS);
+        settings.set("SLOW", "1");
+
+        assert_eq!(
+            settings.to_string(),
+            SETTINGS.replace(
+                ":HAS_CHECKED_RESOURCES=1\n",
+                ":HAS_CHECKED_RESOURCES=1\n:SLOW=1\n"
+            )
+        );
+    }
+
+    #[test]
+    fn set_on_empty_document() {
+        let mut settings = SettingsDocument::parse("");
+        settings.set("VERBOSE", "1");
+
+        assert_eq!(settings.to_string(), ":VERBOSE=1\n");
+    }
+
+    #[test]
+    fn settings_in_file_order() {
+        let settings = SettingsDocument::parse(SETTINGS);
+
+        assert_eq!(
+            settings.settings().map(|(k, _)| k).collect::<Vec<&str>>(),
+            [
+                "ONLINE_SAFE",
+                "AUTO_LAUNCH_GAME",
+                "GAME_PARAMETERS",
+                "HAS_CHECKED_RESOURCES"
+            ]
+        );
+    }
+}
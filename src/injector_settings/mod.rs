@@ -0,0 +1,71 @@
+pub mod document;
+
+use std::{fs, path::PathBuf};
+
+use document::SettingsDocument;
+
+// Settings chosen by the user, as opposed to the injector's internal state
+pub const USER_SETTINGS: [&str; 9] = [
+    "AUTO_LAUNCH_GAME",
+    "RESET_BACKUPS",
+    "AUTO_UPDATE",
+    "VERBOSE",
+    "SLOW",
+    "COMPRESS_TEXTURES",
+    "DISABLE_MULTITHREADING",
+    "ONLINE_SAFE",
+    "GAME_PARAMETERS"
+];
+
+// Settings the injector uses to keep track of its own state
+pub const INTERNAL_SETTINGS: [&str; 3] = ["HAS_READ_FIRST_TIME", "HAS_CHECKED_RESOURCES", "ASSET_VERSION"];
+
+// Check if a setting is chosen by the user
+// Settings the app doesn't know about, such as the ones added by newer injectors, are too
+pub fn is_user_setting(setting: &str) -> bool {
+    !INTERNAL_SETTINGS.contains(&setting)
+}
+
+// Get injector settings path
+pub fn settings_path() -> PathBuf {
+    crate::game_path().join("EternalModInjector Settings.txt")
+}
+
+// Load the injector settings file
+pub fn load() -> Option<SettingsDocument> {
+    fs::read_to_string(settings_path())
+        .ok()
+        .map(|s| SettingsDocument::parse(&s))
+}
+
+// Save the injector settings file
+pub fn save(settings: &SettingsDocument) -> bool {
+    fs::write(settings_path(), settings.to_string()).is_ok()
+}
+
+// Read the user settings from the injector settings file
+pub fn read_user_settings() -> Option<Vec<(String, String)>> {
+    let settings = load()?;
+
+    Some(
+        settings
+            .settings()
+            .filter(|(k, _)| is_user_setting(k))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    )
+}
+
+// Write settings into the injector settings file, keeping everything else as-is
+pub fn write_settings(values: &[(String, String)]) -> bool {
+    let mut settings = match load() {
+        Some(s) => s,
+        None => return false
+    };
+
+    for (key, value) in values {
+        settings.set(key, value);
+    }
+
+    save(&settings)
+}
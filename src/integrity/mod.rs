@@ -0,0 +1,316 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering}
+};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{config, hash};
+
+// Manifest format version supported by this build
+const MANIFEST_FORMAT_VERSION: u64 = 1;
+
+// Set while a manifest is being recorded
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+// Set to stop the running task early
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+// Hashes of the pristine game files, stored in the config folder
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u64,
+    game_path: PathBuf,
+    files: BTreeMap<String, FileHash>
+}
+
+// Size and SHA-256 hash of a game file
+#[derive(Debug, Deserialize, Serialize)]
+struct FileHash {
+    size: u64,
+    sha256: String
+}
+
+// How a game file differs from the manifest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileState {
+    Modded,
+    Corrupted,
+    Missing
+}
+
+// Game file that doesn't match the manifest
+#[derive(Clone, Debug)]
+pub struct ChangedFile {
+    pub name: String,
+    pub state: FileState,
+    pub backup: Option<PathBuf>
+}
+
+// Result of verifying the game files
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub changed: Vec<ChangedFile>
+}
+
+impl VerifyReport {
+    // Get the changed files that can be restored from a backup
+    pub fn restorable(&self) -> Vec<ChangedFile> {
+        self.changed
+            .iter()
+            .filter(|f| f.backup.is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+// Get the manifest path for the current game folder
+fn manifest_path() -> PathBuf {
+    config::config_dir().join(format!(
+        "game-files-{}.json",
+        config::install_id(&crate::game_path())
+    ))
+}
+
+// Get the path of a game file's backup
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup: OsString = path.as_os_str().to_owned();
+    backup.push(".backup");
+    PathBuf::from(backup)
+}
+
+// Get a game file's name relative to the game folder
+fn relative_name(path: &Path) -> String {
+    path.strip_prefix(crate::game_path())
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Get the game files the mod injector can modify, including the ones that only have a backup
+fn game_files() -> Vec<PathBuf> {
+    let game_path = crate::game_path();
+    let base_path = game_path.join("base");
+
+    let mut files = vec![
+        game_path.join("DOOMEternalx64vk.exe"),
+        base_path.join("packagemapspec.json"),
+    ];
+
+    // Get resources and sound banks
+    let folders = [
+        (base_path.clone(), 1, ".resources"),
+        (base_path.join("game"), usize::MAX, ".resources"),
+        (base_path.join("sound").join("soundbanks").join("pc"), 1, ".snd")
+    ];
+
+    for (folder, max_depth, extension) in folders {
+        for entry in WalkDir::new(folder)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_map(|f| f.ok())
+        {
+            let name = entry.file_name().to_string_lossy();
+
+            if let Some(name) = name.strip_suffix(".backup") {
+                if name.ends_with(extension) {
+                    files.push(entry.path().with_extension(""));
+                }
+            }
+            else if name.ends_with(extension) {
+                files.push(entry.path().to_path_buf());
+            }
+        }
+    }
+
+    files.retain(|f| f.is_file() || backup_path(f).is_file());
+    files.sort();
+    files.dedup();
+    files
+}
+
+// Load the manifest for the current game folder
+fn load_manifest() -> Option<Manifest> {
+    let manifest = serde_json::from_slice::<Manifest>(&fs::read(manifest_path()).ok()?).ok()?;
+
+    if manifest.version != MANIFEST_FORMAT_VERSION || manifest.game_path != crate::game_path() {
+        return None;
+    }
+
+    Some(manifest)
+}
+
+// Check if there's a manifest for the current game folder
+pub fn has_manifest() -> bool {
+    load_manifest().is_some()
+}
+
+// Check if a manifest is being recorded
+pub fn is_recording() -> bool {
+    RECORDING.load(Ordering::SeqCst)
+}
+
+// Stop the running task after the file it's working on
+pub fn cancel() {
+    CANCELLED.store(true, Ordering::SeqCst);
+}
+
+// Return an error if the running task was cancelled
+fn check_cancelled() -> io::Result<()> {
+    if CANCELLED.load(Ordering::SeqCst) {
+        return Err(io::Error::new(
+            io::ErrorKind::Interrupted,
+            "the task was cancelled"
+        ));
+    }
+
+    Ok(())
+}
+
+// Record the hashes of the pristine game files, preferring the injector's backups
+// Calls progress with the number of files hashed and the total
+pub fn record(progress: impl Fn(usize, usize)) -> io::Result<usize> {
+    if RECORDING.swap(true, Ordering::SeqCst) {
+        return Err(io::Error::other("game file hashes are already being recorded"));
+    }
+
+    CANCELLED.store(false, Ordering::SeqCst);
+    let result = record_files(progress);
+    RECORDING.store(false, Ordering::SeqCst);
+    result
+}
+
+// Hash the game files and save the manifest
+fn record_files(progress: impl Fn(usize, usize)) -> io::Result<usize> {
+    let files = game_files();
+    let mut manifest = Manifest {
+        version: MANIFEST_FORMAT_VERSION,
+        game_path: crate::game_path(),
+        files: BTreeMap::new()
+    };
+
+    for (i, file) in files.iter().enumerate() {
+        check_cancelled()?;
+        progress(i, files.len());
+
+        // Backups hold the unmodded file
+        let backup = backup_path(file);
+        let source = if backup.is_file() { backup } else { file.clone() };
+
+        manifest.files.insert(
+            relative_name(file),
+            FileHash {
+                size: fs::metadata(&source)?.len(),
+                sha256: hash::hash_file(&source)?
+            }
+        );
+    }
+
+    progress(files.len(), files.len());
+
+    // Save manifest
+    fs::create_dir_all(config::config_dir())?;
+    fs::write(manifest_path(), serde_json::to_vec_pretty(&manifest)?)?;
+
+    Ok(manifest.files.len())
+}
+
+// Compare the game files against the manifest and the backups
+// Calls progress with the number of files checked and the total
+pub fn verify(progress: impl Fn(usize, usize)) -> io::Result<VerifyReport> {
+    let manifest = load_manifest().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no game file hashes were recorded for this game folder"
+        )
+    })?;
+
+    let game_path = crate::game_path();
+    let mut report = VerifyReport::default();
+    CANCELLED.store(false, Ordering::SeqCst);
+
+    for (i, (name, expected)) in manifest.files.iter().enumerate() {
+        check_cancelled()?;
+        progress(i, manifest.files.len());
+
+        let path = game_path.join(name);
+        report.checked += 1;
+
+        // Check the size first to avoid hashing files that obviously changed
+        let exists = match fs::metadata(&path) {
+            Ok(m)
+                if m.len() == expected.size && hash::hash_file(&path).is_ok_and(|h| h == expected.sha256) =>
+            {
+                continue
+            },
+            Ok(_) => true,
+            Err(_) => false
+        };
+
+        // Use the backup if it still holds the pristine file
+        let backup = Some(backup_path(&path)).filter(|b| {
+            fs::metadata(b).is_ok_and(|m| m.len() == expected.size)
+                && hash::hash_file(b).is_ok_and(|h| h == expected.sha256)
+        });
+
+        // Files with a pristine backup were modified by the injector
+        let state = if !exists {
+            FileState::Missing
+        }
+        else if backup.is_some() {
+            FileState::Modded
+        }
+        else {
+            FileState::Corrupted
+        };
+
+        report.changed.push(ChangedFile {
+            name: name.clone(),
+            state,
+            backup
+        });
+    }
+
+    progress(manifest.files.len(), manifest.files.len());
+
+    Ok(report)
+}
+
+// Restore changed files from their backups, returning the names of the ones that failed
+// Calls progress with the number of files restored and the total
+pub fn restore(files: &[ChangedFile], progress: impl Fn(usize, usize)) -> Vec<String> {
+    let game_path = crate::game_path();
+    let mut failed = Vec::new();
+    CANCELLED.store(false, Ordering::SeqCst);
+
+    for (i, file) in files.iter().enumerate() {
+        // Files left when cancelled weren't restored
+        if check_cancelled().is_err() {
+            failed.extend(files[i..].iter().map(|f| f.name.clone()));
+            break;
+        }
+
+        progress(i, files.len());
+
+        let restored = match &file.backup {
+            Some(backup) => fs::copy(backup, game_path.join(&file.name)).is_ok(),
+            None => false
+        };
+
+        if !restored {
+            failed.push(file.name.clone());
+        }
+    }
+
+    progress(files.len(), files.len());
+
+    failed
+}
@@ -0,0 +1,402 @@
+// Type of value a known parameter takes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    // 0 or 1
+    Bool,
+    // Whole number
+    Integer
+}
+
+// Launch parameter known to work with DOOM Eternal
+pub struct KnownParameter {
+    pub name: &'static str,
+    pub kind: ValueKind,
+    pub default: &'static str,
+    pub description: &'static str
+}
+
+// Known DOOM Eternal launch parameters
+pub static KNOWN_PARAMETERS: [KnownParameter; 8] = [
+    KnownParameter {
+        name: "+com_skipIntroVideo",
+        kind: ValueKind::Bool,
+        default: "1",
+        description: "Skip the intro videos"
+    },
+    KnownParameter {
+        name: "+com_skipSignInManager",
+        kind: ValueKind::Bool,
+        default: "1",
+        description: "Skip the Bethesda.net sign in"
+    },
+    KnownParameter {
+        name: "+com_skipKeyPressOnLoadScreens",
+        kind: ValueKind::Bool,
+        default: "1",
+        description: "Don't wait for a key press after loading a level"
+    },
+    KnownParameter {
+        name: "+in_terminal",
+        kind: ValueKind::Bool,
+        default: "1",
+        description: "Open the developer console in a terminal window"
+    },
+    KnownParameter {
+        name: "+r_fullscreen",
+        kind: ValueKind::Integer,
+        default: "0",
+        description: "Window mode: 0 for windowed, 1 for fullscreen, 2 for borderless"
+    },
+    KnownParameter {
+        name: "+r_windowWidth",
+        kind: ValueKind::Integer,
+        default: "1920",
+        description: "Window width in pixels"
+    },
+    KnownParameter {
+        name: "+r_windowHeight",
+        kind: ValueKind::Integer,
+        default: "1080",
+        description: "Window height in pixels"
+    },
+    KnownParameter {
+        name: "+com_maxFps",
+        kind: ValueKind::Integer,
+        default: "0",
+        description: "Frame rate limit, 0 for unlimited"
+    }
+];
+
+// Launch parameter, such as "+com_skipIntroVideo 1"
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub value: String
+}
+
+// Problem found when checking a parameter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    // The game won't understand the parameter
+    Error(String),
+    // The parameter is valid, but probably not what was meant
+    Warning(String)
+}
+
+// Split launch parameters into words, keeping quoted words together
+fn split_words(parameters: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in parameters.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.push(c);
+            },
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            },
+            c => word.push(c)
+        }
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+// Parse launch parameters
+// Words that don't belong to a parameter are kept as parameters of their own, so check can report them
+pub fn parse(parameters: &str) -> Vec<Parameter> {
+    let mut parsed: Vec<Parameter> = Vec::new();
+
+    for word in split_words(parameters) {
+        // Console variables take the next word as their value
+        match parsed.last_mut() {
+            Some(last) if last.name.starts_with('+') && last.value.is_empty() && !is_name(&word) => {
+                last.value = word;
+            },
+            _ => parsed.push(Parameter {
+                name: word,
+                value: String::new()
+            })
+        }
+    }
+
+    parsed
+}
+
+// Check if a word starts a new parameter
+fn is_name(word: &str) -> bool {
+    word.starts_with('+') || (word.starts_with('-') && word.parse::<f64>().is_err())
+}
+
+// Join parameters back into a launch parameters string
+pub fn serialize(parameters: &[Parameter]) -> String {
+    parameters
+        .iter()
+        .filter(|p| !p.name.is_empty())
+        .map(|p| {
+            if p.value.is_empty() {
+                p.name.clone()
+            }
+            else {
+                format!("{} {}", p.name, p.value)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Find a known parameter by name, ignoring case like the game does
+pub fn find_known(name: &str) -> Option<&'static KnownParameter> {
+    KNOWN_PARAMETERS
+        .iter()
+        .find(|k| k.name.eq_ignore_ascii_case(name))
+}
+
+// Check a parameter for mistakes
+pub fn check(parameter: &Parameter) -> Option<Problem> {
+    let name = parameter.name.as_str();
+
+    if name.is_empty() {
+        return Some(Problem::Error(String::from("The parameter needs a name.")));
+    }
+
+    if !is_name(name) {
+        return Some(Problem::Error(format!(
+            "\"{}\" doesn't belong to any parameter. Parameters start with + or -.",
+            name
+        )));
+    }
+
+    if name.len() == 1
+        || !name[1..]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return Some(Problem::Error(format!(
+            "\"{}\" isn't a valid parameter name.",
+            name
+        )));
+    }
+
+    if parameter.value.matches('"').count() % 2 != 0 {
+        return Some(Problem::Error(String::from("The value has an unclosed quote.")));
+    }
+
+    if parameter.value.chars().any(char::is_whitespace) && !parameter.value.starts_with('"') {
+        return Some(Problem::Error(String::from("Values with spaces must be quoted.")));
+    }
+
+    // Check the value of known parameters
+    if let Some(known) = find_known(name) {
+        let value = parameter.value.as_str();
+
+        return match known.kind {
+            ValueKind::Bool if value != "0" && value != "1" => {
+                Some(Problem::Error(format!("{} must be 0 or 1.", known.name)))
+            },
+            ValueKind::Integer if value.parse::<u32>().is_err() => {
+                Some(Problem::Error(format!("{} must be a whole number.", known.name)))
+            },
+            _ => None
+        };
+    }
+
+    if name.starts_with('+') && parameter.value.is_empty() {
+        return Some(Problem::Warning(format!("{} has no value.", name)));
+    }
+
+    // Look for typos of known parameters
+    KNOWN_PARAMETERS
+        .iter()
+        .find(|k| edit_distance(&k.name.to_lowercase(), &name.to_lowercase()) <= 2)
+        .map(|k| Problem::Warning(format!("Unknown parameter. Did you mean {}?", k.name)))
+}
+
+// Get the number of single character edits needed to turn a into b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            }
+            else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Create a parameter
+    fn parameter(name: &str, value: &str) -> Parameter {
+        Parameter {
+            name: name.to_owned(),
+            value: value.to_owned()
+        }
+    }
+
+    #[test]
+    fn parses_names_and_values() {
+        assert_eq!(
+            parse("+com_skipIntroVideo 1  -fullscreen +r_windowWidth 2560"),
+            vec![
+                parameter("+com_skipIntroVideo", "1"),
+                parameter("-fullscreen", ""),
+                parameter("+r_windowWidth", "2560")
+            ]
+        );
+        assert_eq!(parse(""), Vec::new());
+        assert_eq!(parse("  \t "), Vec::new());
+    }
+
+    #[test]
+    fn parses_negative_values() {
+        assert_eq!(
+            parse("+g_fov -1.5 +com_maxFps -10 -nosound"),
+            vec![
+                parameter("+g_fov", "-1.5"),
+                parameter("+com_maxFps", "-10"),
+                parameter("-nosound", "")
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_quoted_values_with_spaces() {
+        assert_eq!(
+            parse("+exec \"my config.cfg\" +com_skipIntroVideo 1"),
+            vec![
+                parameter("+exec", "\"my config.cfg\""),
+                parameter("+com_skipIntroVideo", "1")
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_orphan_words() {
+        assert_eq!(
+            parse("skipIntro +in_terminal 1 0"),
+            vec![
+                parameter("skipIntro", ""),
+                parameter("+in_terminal", "1"),
+                parameter("0", "")
+            ]
+        );
+        assert_eq!(
+            parse("-windowed 1"),
+            vec![parameter("-windowed", ""), parameter("1", "")]
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for parameters in [
+            "+com_skipIntroVideo 1 -fullscreen +r_windowWidth 2560",
+            "+exec \"my config.cfg\" +g_fov -1.5",
+            "orphan +in_terminal 1 0",
+            ""
+        ] {
+            assert_eq!(serialize(&parse(parameters)), parameters);
+        }
+
+        assert_eq!(serialize(&parse("  +com_maxFps   144  ")), "+com_maxFps 144");
+    }
+
+    #[test]
+    fn serialize_skips_unnamed_parameters() {
+        assert_eq!(
+            serialize(&[parameter("", "1"), parameter("+in_terminal", "1")]),
+            "+in_terminal 1"
+        );
+    }
+
+    #[test]
+    fn checks_names() {
+        assert!(matches!(check(&parameter("", "1")), Some(Problem::Error(_))));
+        assert!(matches!(check(&parameter("orphan", "")), Some(Problem::Error(_))));
+        assert!(matches!(check(&parameter("+", "1")), Some(Problem::Error(_))));
+        assert!(matches!(
+            check(&parameter("+bad/name", "1")),
+            Some(Problem::Error(_))
+        ));
+        assert_eq!(check(&parameter("-nosound", "")), None);
+    }
+
+    #[test]
+    fn checks_values() {
+        assert!(matches!(
+            check(&parameter("+exec", "\"my config.cfg")),
+            Some(Problem::Error(_))
+        ));
+        assert!(matches!(
+            check(&parameter("+exec", "my config.cfg")),
+            Some(Problem::Error(_))
+        ));
+        assert_eq!(check(&parameter("+exec", "\"my config.cfg\"")), None);
+        assert!(matches!(
+            check(&parameter("+my_cvar", "")),
+            Some(Problem::Warning(_))
+        ));
+    }
+
+    #[test]
+    fn checks_known_parameter_values() {
+        assert_eq!(check(&parameter("+COM_SKIPINTROVIDEO", "1")), None);
+        assert!(matches!(
+            check(&parameter("+com_skipIntroVideo", "2")),
+            Some(Problem::Error(_))
+        ));
+        assert_eq!(check(&parameter("+com_maxFps", "144")), None);
+        assert!(matches!(
+            check(&parameter("+com_maxFps", "-10")),
+            Some(Problem::Error(_))
+        ));
+        assert!(matches!(
+            check(&parameter("+r_windowWidth", "wide")),
+            Some(Problem::Error(_))
+        ));
+    }
+
+    #[test]
+    fn suggests_known_parameters_for_typos() {
+        assert_eq!(
+            check(&parameter("+com_skipIntroVideos", "1")),
+            Some(Problem::Warning(String::from(
+                "Unknown parameter. Did you mean +com_skipIntroVideo?"
+            )))
+        );
+        assert_eq!(check(&parameter("+g_fov", "90")), None);
+    }
+
+    #[test]
+    fn measures_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("+in_terminal", "+in_terminal"), 0);
+        assert_eq!(edit_distance("+r_fullscren", "+r_fullscreen"), 1);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{cell::RefCell, rc::Rc};
+
+use adw::prelude::*;
+use gtk::{
+    glib::{self, clone},
+    Align, ApplicationWindow, Box, Builder, Button, DropDown, Entry, Label, ListBox, ListBoxRow, Orientation,
+    StringList
+};
+
+use crate::launch_parameters::{self, Parameter, Problem, KNOWN_PARAMETERS};
+
+// Controls editing one parameter
+#[derive(Clone)]
+struct ParameterRow {
+    row: ListBoxRow,
+    name: Entry,
+    value: Entry,
+    info: Label
+}
+
+// Parameter list and the widgets showing its state
+#[derive(Clone)]
+struct Editor {
+    list: ListBox,
+    preview: Label,
+    apply_button: Button,
+    rows: Rc<RefCell<Vec<ParameterRow>>>
+}
+
+impl Editor {
+    // Get the parameters in the list
+    fn parameters(&self) -> Vec<Parameter> {
+        self.rows
+            .borrow()
+            .iter()
+            .map(|r| Parameter {
+                name: r.name.text().trim().to_owned(),
+                value: r.value.text().trim().to_owned()
+            })
+            .collect()
+    }
+
+    // Add a row for a parameter
+    fn add_row(&self, parameter: &Parameter) -> ParameterRow {
+        let row_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .margin_start(6)
+            .margin_end(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+
+        let entry_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        row_box.append(&entry_box);
+
+        let name = Entry::builder()
+            .text(&parameter.name)
+            .placeholder_text("+name")
+            .width_chars(28)
+            .build();
+        entry_box.append(&name);
+
+        let value = Entry::builder()
+            .text(&parameter.value)
+            .placeholder_text("Value")
+            .hexpand(true)
+            .build();
+        entry_box.append(&value);
+
+        let remove_button = Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_valign(Align::Center);
+        remove_button.set_tooltip_text(Some("Remove parameter"));
+        entry_box.append(&remove_button);
+
+        // Description or problem of the parameter
+        let info = Label::builder()
+            .halign(Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .build();
+        row_box.append(&info);
+
+        let row = ListBoxRow::builder().child(&row_box).activatable(false).build();
+        self.list.append(&row);
+
+        let parameter_row = ParameterRow {
+            row,
+            name,
+            value,
+            info
+        };
+        self.rows.borrow_mut().push(parameter_row.clone());
+
+        // Check the parameter as it's edited
+        for entry in [&parameter_row.name, &parameter_row.value] {
+            entry.connect_changed(clone!(
+                #[strong(rename_to = editor)]
+                self,
+                move |_| {
+                    editor.refresh();
+                }
+            ));
+        }
+
+        // Remove row
+        remove_button.connect_clicked(clone!(
+            #[strong(rename_to = editor)]
+            self,
+            #[weak(rename_to = row)]
+            parameter_row.row,
+            move |_| {
+                editor.rows.borrow_mut().retain(|r| r.row != row);
+                editor.list.remove(&row);
+                editor.refresh();
+            }
+        ));
+
+        self.refresh();
+        parameter_row
+    }
+
+    // Show each parameter's problems and the resulting launch parameters
+    fn refresh(&self) {
+        let mut has_errors = false;
+
+        for row in self.rows.borrow().iter() {
+            let parameter = Parameter {
+                name: row.name.text().trim().to_owned(),
+                value: row.value.text().trim().to_owned()
+            };
+
+            for class in ["error", "warning", "dim-label"] {
+                row.info.remove_css_class(class);
+            }
+
+            let (text, class) = match launch_parameters::check(&parameter) {
+                Some(Problem::Error(e)) => {
+                    has_errors = true;
+                    (e, "error")
+                },
+                Some(Problem::Warning(w)) => (w, "warning"),
+                None => match launch_parameters::find_known(&parameter.name) {
+                    Some(known) => (known.description.to_owned(), "dim-label"),
+                    None => (String::new(), "dim-label")
+                }
+            };
+
+            row.info.set_visible(!text.is_empty());
+            row.info.set_text(&text);
+            row.info.add_css_class(class);
+        }
+
+        let parameters = launch_parameters::serialize(&self.parameters());
+
+        self.preview.set_text(&if parameters.is_empty() {
+            String::from("No launch parameters.")
+        }
+        else {
+            format!("Launch parameters: {}", parameters)
+        });
+
+        // Don't save parameters the game won't understand
+        self.apply_button.set_sensitive(!has_errors);
+    }
+}
+
+// Create launch parameters editor, calling on_apply with the edited parameters
+pub fn create(
+    parent_window: &ApplicationWindow, parameters: &str, on_apply: impl Fn(String) + 'static
+) -> ApplicationWindow {
+    // Create builder from UI file
+    let ui_src = include_str!("launch_parameters.ui");
+    let builder = Builder::from_string(ui_src);
+
+    // Get window
+    let window = builder
+        .object::<ApplicationWindow>("LaunchParametersWindow")
+        .unwrap();
+    window.set_transient_for(Some(parent_window));
+
+    let editor = Editor {
+        list: builder.object::<ListBox>("ParameterList").unwrap(),
+        preview: builder.object::<Label>("ParametersPreview").unwrap(),
+        apply_button: builder.object::<Button>("ApplyParameters").unwrap(),
+        rows: Rc::new(RefCell::new(Vec::new()))
+    };
+
+    // Fill parameter list
+    for parameter in launch_parameters::parse(parameters) {
+        editor.add_row(&parameter);
+    }
+
+    editor.refresh();
+
+    // Fill known parameters list
+    let known_parameters = builder.object::<DropDown>("KnownParameters").unwrap();
+    let known_names = KNOWN_PARAMETERS
+        .iter()
+        .map(|k| format!("{} ({})", k.description, k.name))
+        .collect::<Vec<String>>();
+    known_parameters.set_model(Some(&StringList::new(
+        &known_names.iter().map(String::as_str).collect::<Vec<&str>>()
+    )));
+
+    // Add known parameter, or focus it if it's already in the list
+    let add_known_button = builder.object::<Button>("AddKnownParameter").unwrap();
+
+    add_known_button.connect_clicked(clone!(
+        #[strong]
+        editor,
+        #[weak]
+        known_parameters,
+        move |_| {
+            let known = match KNOWN_PARAMETERS.get(known_parameters.selected() as usize) {
+                Some(k) => k,
+                None => return
+            };
+
+            let existing = editor
+                .rows
+                .borrow()
+                .iter()
+                .find(|r| r.name.text().trim().eq_ignore_ascii_case(known.name))
+                .cloned();
+
+            let row = existing.unwrap_or_else(|| {
+                editor.add_row(&Parameter {
+                    name: known.name.to_owned(),
+                    value: known.default.to_owned()
+                })
+            });
+
+            row.value.grab_focus();
+        }
+    ));
+
+    // Add empty parameter to fill in
+    let add_custom_button = builder.object::<Button>("AddCustomParameter").unwrap();
+
+    add_custom_button.connect_clicked(clone!(
+        #[strong]
+        editor,
+        move |_| {
+            editor.add_row(&Parameter::default()).name.grab_focus();
+        }
+    ));
+
+    // Apply parameters and close
+    editor.apply_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[strong]
+        editor,
+        move |_| {
+            on_apply(launch_parameters::serialize(&editor.parameters()));
+            window.close();
+        }
+    ));
+
+    window
+}
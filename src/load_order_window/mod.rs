@@ -0,0 +1,257 @@
+use std::{sync::mpsc, thread};
+
+use adw::{prelude::*, AlertDialog};
+use gtk::{
+    gdk::{ContentProvider, DragAction},
+    gio::ListStore,
+    glib::{self, clone, MainContext},
+    pango::EllipsizeMode,
+    Align, ApplicationWindow, Box, Builder, Button, DragSource, DropTarget, Image, Label, ListBox,
+    ListBoxRow, Orientation, Widget, WidgetPaintable
+};
+
+use crate::{eternal_mod, mod_data::ModData, model::Model};
+
+// Create load order window
+pub fn create(parent_window: &ApplicationWindow, model: &Model) -> ApplicationWindow {
+    // Create builder from UI file
+    let ui_src = include_str!("load_order.ui");
+    let builder = Builder::from_string(ui_src);
+
+    // Get window
+    let window = builder.object::<ApplicationWindow>("LoadOrderWindow").unwrap();
+    window.set_transient_for(Some(parent_window));
+
+    // Get enabled mods sorted by load priority
+    let mut mods = model
+        .items()
+        .into_iter()
+        .filter(|m| m.is_enabled() && m.is_valid())
+        .collect::<Vec<ModData>>();
+
+    mods.sort_by_key(|m| {
+        (
+            m.eternal_mod().load_priority.unwrap_or(0),
+            m.filename().unwrap().to_lowercase()
+        )
+    });
+
+    // Create list store with the current load order
+    let store = ListStore::new::<ModData>();
+    store.extend_from_slice(&mods);
+
+    // Bind listbox to store
+    let listbox = builder.object::<ListBox>("LoadOrderList").unwrap();
+
+    listbox.bind_model(
+        Some(&store),
+        clone!(
+            #[strong]
+            store,
+            move |item| create_row(item.downcast_ref::<ModData>().unwrap(), &store).upcast::<Widget>()
+        )
+    );
+
+    // Init save load order button
+    let save_button = builder.object::<Button>("SaveLoadOrder").unwrap();
+
+    save_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        store,
+        move |_| {
+            // Get mods whose load priority changed
+            // Mods at the top of the list get the lowest value, so they are loaded last
+            let changed_mods = store
+                .iter::<ModData>()
+                .filter_map(|m| m.ok())
+                .enumerate()
+                .map(|(i, m)| (m, i as i64 + 1))
+                .filter(|(m, p)| m.eternal_mod().load_priority != Some(*p))
+                .map(|(m, p)| (m.filename().unwrap(), p))
+                .collect::<Vec<(String, i64)>>();
+
+            // Disable window
+            window.set_sensitive(false);
+
+            let (tx, rx) = mpsc::channel();
+
+            // Write new load priorities
+            thread::spawn(move || {
+                let mods_folder = crate::game_path().join("Mods");
+                let mut errors = Vec::new();
+
+                for (filename, load_priority) in &changed_mods {
+                    if let Err(e) =
+                        eternal_mod::write_load_priority(&mods_folder.join(filename), *load_priority)
+                    {
+                        errors.push(format!("{}: {}", filename, e));
+                    }
+                }
+
+                tx.send((changed_mods.len(), errors)).unwrap();
+            });
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                window,
+                async move {
+                    if let Ok((changed, errors)) = rx.recv() {
+                        // Create end prompt
+                        let dialog = if errors.is_empty() {
+                            AlertDialog::builder()
+                                .heading("Done.")
+                                .body(format!("The load priority of {} mods was updated.", changed))
+                                .default_response("ok")
+                                .close_response("ok")
+                                .build()
+                        }
+                        else {
+                            AlertDialog::builder()
+                                .heading("Failed to update the load order.")
+                                .body(format!(
+                                    "The following mods couldn't be updated:\n\n{}",
+                                    errors.join("\n")
+                                ))
+                                .default_response("ok")
+                                .close_response("ok")
+                                .build()
+                        };
+
+                        dialog.add_responses(&[("ok", "_Ok")]);
+
+                        // WORKAROUND: AlertDialog's close response doesn't work
+                        dialog.connect_destroy(|dialog| {
+                            dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+                        });
+
+                        let signal = dialog.connect_response(
+                            None,
+                            clone!(
+                                #[weak]
+                                window,
+                                move |_, _| {
+                                    // Close window if everything was saved
+                                    if errors.is_empty() {
+                                        window.close();
+                                    }
+                                    else {
+                                        window.set_sensitive(true);
+                                    }
+                                }
+                            )
+                        );
+
+                        // WORKAROUND: AlertDialog's close response doesn't work
+                        dialog.connect_response(None, move |d, _| {
+                            d.block_signal(&signal);
+                        });
+
+                        dialog.present(Some(&window));
+                    }
+                }
+            ));
+        }
+    ));
+
+    window
+}
+
+// Create a draggable list row for a mod
+fn create_row(mod_data: &ModData, store: &ListStore) -> ListBoxRow {
+    let filename = mod_data.filename().unwrap();
+
+    // Create row contents
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+
+    row_box.append(&Image::from_icon_name("list-drag-handle-symbolic"));
+
+    let name = Label::builder()
+        .label(&filename)
+        .halign(Align::Start)
+        .hexpand(true)
+        .ellipsize(EllipsizeMode::End)
+        .build();
+    row_box.append(&name);
+
+    let load_priority = Label::builder()
+        .label(format!("Current priority: {}", mod_data.load_priority().unwrap()))
+        .halign(Align::End)
+        .build();
+    load_priority.add_css_class("dim-label");
+    row_box.append(&load_priority);
+
+    let row = ListBoxRow::builder().child(&row_box).build();
+
+    // Allow dragging the row
+    let drag_source = DragSource::new();
+    drag_source.set_actions(DragAction::MOVE);
+
+    drag_source.connect_prepare(move |_, _, _| Some(ContentProvider::for_value(&filename.to_value())));
+
+    drag_source.connect_drag_begin(clone!(
+        #[weak]
+        row,
+        move |source, _| {
+            // Use the row as the drag icon
+            source.set_icon(Some(&WidgetPaintable::new(Some(&row))), 0, 0);
+        }
+    ));
+
+    row.add_controller(drag_source);
+
+    // Move dragged mods to this row's position
+    let drop_target = DropTarget::new(String::static_type(), DragAction::MOVE);
+
+    drop_target.connect_drop(clone!(
+        #[weak]
+        store,
+        #[weak]
+        row,
+        #[upgrade_or]
+        false,
+        move |_, value, _, _| {
+            match value.get::<String>() {
+                Ok(filename) => move_mod(&store, &filename, row.index()),
+                Err(_) => false
+            }
+        }
+    ));
+
+    row.add_controller(drop_target);
+
+    row
+}
+
+// Move a mod to a new position in the load order
+fn move_mod(store: &ListStore, filename: &str, target_index: i32) -> bool {
+    // Find the dragged mod
+    let source_index = match (0..store.n_items()).find(|&i| {
+        store
+            .item(i)
+            .and_downcast::<ModData>()
+            .is_some_and(|m| m.filename().unwrap() == filename)
+    }) {
+        Some(i) => i,
+        None => return false
+    };
+
+    if target_index < 0 || source_index == target_index as u32 {
+        return false;
+    }
+
+    // Move mod
+    let item = store.item(source_index).unwrap();
+    store.remove(source_index);
+    store.insert(target_index as u32, &item);
+
+    true
+}
@@ -1,13 +1,37 @@
 #![windows_subsystem = "windows"]
 
 mod advanced_window;
+mod archive;
+mod backups;
+mod backups_window;
+mod cli;
+mod config;
+mod conflicts;
+mod conflicts_window;
+mod eternal_mod;
+mod game_update;
+mod hash;
 mod injector;
+mod injector_console;
+mod injector_settings;
+mod integrity;
+mod launch_parameters;
+mod launch_parameters_window;
+mod load_order_window;
 mod manager_window;
 pub mod mod_data;
 mod mod_list_row;
+mod modding_tools;
 mod model;
+mod modpack;
+mod mods;
+mod online_safety;
+mod profiles;
+mod profiles_window;
+mod remote;
+mod steam;
 
-use std::path::PathBuf;
+use std::{env, path::PathBuf, sync::RwLock};
 
 use adw::{prelude::*, Application};
 use gtk::{
@@ -17,16 +41,35 @@ use gtk::{
     CssProvider, Window
 };
 use model::Model;
-use once_cell::sync::OnceCell;
 
-// DOOM Eternal game path
-static GAME_PATH: OnceCell<PathBuf> = OnceCell::new();
+// DOOM Eternal game path, which can be switched while running
+static GAME_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// Get the game path, which must be set already
+fn game_path() -> PathBuf {
+    try_game_path().unwrap()
+}
+
+// Get the game path if it's set
+fn try_game_path() -> Option<PathBuf> {
+    GAME_PATH.read().unwrap().clone()
+}
+
+// Set the game path
+fn set_game_path(path: PathBuf) {
+    *GAME_PATH.write().unwrap() = Some(path);
+}
 
 fn main() -> ExitCode {
+    // Run command line commands without a window
+    if let Some(exit_code) = cli::run(&env::args().collect::<Vec<String>>()) {
+        return exit_code;
+    }
+
     // Create app
     let app = Application::new(
         Some("io.github.brunoanc.eternalmodmanager"),
-        ApplicationFlags::HANDLES_OPEN | ApplicationFlags::NON_UNIQUE
+        ApplicationFlags::HANDLES_OPEN
     );
 
     // Set app name on X11
@@ -52,6 +95,22 @@ fn main() -> ExitCode {
 
 // Initialize app components
 fn activate_app(app: &Application, files: &[File]) {
+    // Show the existing window if the app was launched again
+    if let Some(window) = app.active_window() {
+        window.present();
+
+        // Switch to the game folder it was launched with
+        if let Some(path) = files.first().and_then(|f| f.path()) {
+            let _ = WidgetExt::activate_action(
+                &window,
+                "win.switch-game-path",
+                Some(&path.to_string_lossy().to_variant())
+            );
+        }
+
+        return;
+    }
+
     #[cfg(target_os = "windows")]
     // Set dark theme if needed on Windows
     set_theme_windows();
@@ -68,6 +127,14 @@ fn activate_app(app: &Application, files: &[File]) {
     // Show window
     manager_window.present();
 
+    // Export mod list over D-Bus
+    remote::export_mod_list(app, &model);
+
+    // Load online safety rules
+    if let Some(err) = online_safety::init_rules() {
+        manager_window::show_rules_error(&manager_window, &err);
+    }
+
     // Get game path
     manager_window::get_game_path(&manager_window, files, &model);
 }
@@ -1,26 +1,41 @@
 use std::{
-    env,
-    fs::{self, File},
+    env, fs, io,
     path::{Path, PathBuf},
-    sync::mpsc,
+    sync::{
+        mpsc::{self, TryRecvError},
+        Mutex
+    },
     thread,
     time::Duration
 };
 
-use adw::{prelude::*, AlertDialog, Application};
+use adw::{prelude::*, AlertDialog, Application, Banner, ResponseAppearance};
 use gtk::{
     gdk::{Display, DragAction, FileList, Monitor},
-    gio::{Cancellable, File as GioFile},
-    glib::{self, clone, KeyFile, KeyFileFlags, MainContext},
-    ApplicationWindow, Builder, Button, CheckButton, DropTarget, FileDialog, Label, ListBox, ScrolledWindow,
-    Widget
+    gio::{Cancellable, File as GioFile, ListStore, Menu, MenuItem, SimpleAction},
+    glib::{self, clone, ControlFlow, MainContext, Propagation, VariantTy},
+    ApplicationWindow, Builder, Button, CheckButton, DropDown, DropTarget, FileDialog, FileFilter, Label,
+    ListBox, MenuButton, ProgressBar, ScrolledWindow, Widget
 };
-use im::Vector;
-use notify::RecursiveMode;
-use serde_json::{Result, Value};
-use zip::ZipArchive;
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::Debouncer;
 
-use crate::{advanced_window, injector, mod_data::ModData, mod_list_row::ListBoxRow, model::Model};
+#[cfg(target_os = "linux")]
+use crate::modding_tools::release::{Release, ReleaseError};
+use crate::{
+    advanced_window, backups, config, conflicts_window, game_update,
+    injector_console::InjectorConsole,
+    injector_settings,
+    integrity::{self, FileState, VerifyReport},
+    load_order_window,
+    mod_data::ModData,
+    mod_list_row::ListBoxRow,
+    modding_tools::{self, ToolsError},
+    model::Model,
+    modpack, mods,
+    online_safety::{self, RulesError},
+    profiles_window, remote, steam
+};
 
 // Create manager window
 pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
@@ -66,73 +81,260 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
         window.set_height_request(700);
     }
 
+    // Init mod injector console
+    let injector_console = InjectorConsole::new(&builder);
+
+    // Controls to disable while the mod injector runs
+    let injector_controls = [
+        "ModList",
+        "EnableAllCheckBox",
+        "RunInjector",
+        "AdvancedOptions",
+        "MainMenuButton",
+        "GameFolderButton"
+    ]
+    .map(|id| builder.object::<Widget>(id).unwrap());
+
+    // Don't close the window while the mod injector runs
+    window.connect_close_request(clone!(
+        #[strong]
+        injector_console,
+        move |window| {
+            if !injector_console.is_running() {
+                return Propagation::Proceed;
+            }
+
+            show_message(
+                window,
+                "The mod injector is still running.",
+                "Wait for it to finish before closing EternalModManager."
+            );
+
+            Propagation::Stop
+        }
+    ));
+
     // Init run mod injector button
     let injector_button = builder.object::<Button>("RunInjector").unwrap();
 
+    // Banner shown when the game was updated
+    let banner = builder.object::<Banner>("GameUpdatedBanner").unwrap();
+
     injector_button.connect_clicked(clone!(
         #[weak]
         window,
+        #[weak]
+        model,
+        #[weak]
+        banner,
+        #[strong]
+        injector_console,
+        #[strong]
+        injector_controls,
         move |_| {
+            // The recorded hashes would include the modded files
+            if integrity::is_recording() {
+                show_message(
+                    &window,
+                    "Game file hashes are being recorded.",
+                    "Try again once EternalModManager finishes recording them."
+                );
+                return;
+            }
+
+            // Get enabled mods that need a newer mod loader
+            let incompatible_mods = model
+                .items()
+                .iter()
+                .filter(|m| m.is_enabled() && !m.is_compatible())
+                .map(|m| m.filename().unwrap())
+                .collect::<Vec<String>>();
+
+            if incompatible_mods.is_empty() {
+                run_injector(&window, &model, &banner, &injector_console, &injector_controls);
+                return;
+            }
+
             // Disable parent window
             window.set_sensitive(false);
 
+            // Create warning dialog
+            let warning_dialog = AlertDialog::builder()
+                .heading("Some mods need a newer mod loader.")
+                .body(format!(
+                    "The following enabled mods require a newer version of the modding tools than the one \
+                     installed, and might not work correctly:\n\n{}\n\nDo you want to run the mod injector \
+                     anyway?",
+                    incompatible_mods.join("\n")
+                ))
+                .default_response("no")
+                .close_response("no")
+                .build();
+
+            warning_dialog.add_responses(&[("yes", "_Run anyway"), ("no", "_Cancel")]);
+            warning_dialog.set_response_appearance("yes", ResponseAppearance::Destructive);
+
+            // WORKAROUND: AlertDialog's close response doesn't work
+            warning_dialog.connect_destroy(|dialog| {
+                dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+            });
+
+            let signal = warning_dialog.connect_response(
+                None,
+                clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    model,
+                    #[weak]
+                    banner,
+                    #[strong]
+                    injector_console,
+                    #[strong]
+                    injector_controls,
+                    move |_, result| {
+                        // Re-enable parent window
+                        window.set_sensitive(true);
+
+                        // Check user selection
+                        if result == "yes" {
+                            run_injector(&window, &model, &banner, &injector_console, &injector_controls);
+                        }
+                    }
+                )
+            );
+
+            // WORKAROUND: AlertDialog's close response doesn't work
+            warning_dialog.connect_response(None, move |d, _| {
+                d.block_signal(&signal);
+            });
+
+            warning_dialog.present(Some(&window));
+        }
+    ));
+
+    // Add app actions for remote control
+    remote::add_actions(app, &window, &injector_button);
+
+    // Init game folder switcher, listing the used game folders every time it's opened
+    let game_folder_button = builder.object::<MenuButton>("GameFolderButton").unwrap();
+    game_folder_button.set_menu_model(Some(&game_path_menu()));
+
+    game_folder_button.set_create_popup_func(|button| {
+        button.set_menu_model(Some(&game_path_menu()));
+    });
+
+    // Create action "switch-game-path", taking the game folder to switch to
+    let action_switch_game_path = SimpleAction::new_stateful(
+        "switch-game-path",
+        Some(VariantTy::STRING),
+        &String::new().to_variant()
+    );
+
+    action_switch_game_path.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        model,
+        move |_, parameter| {
+            if let Some(path) = parameter.and_then(|p| p.get::<String>()) {
+                switch_game_path(&window, &model, PathBuf::from(path));
+            }
+        }
+    ));
+
+    // Show the current game folder's name
+    action_switch_game_path.connect_state_notify(clone!(
+        #[weak]
+        game_folder_button,
+        move |action| {
+            let path = PathBuf::from(action.state().and_then(|s| s.get::<String>()).unwrap_or_default());
+
+            game_folder_button.set_label(&path.file_name().unwrap_or_default().to_string_lossy());
+            game_folder_button.set_tooltip_text(Some(&format!("Game folder: {}", path.display())));
+            game_folder_button.set_visible(true);
+        }
+    ));
+
+    window.add_action(&action_switch_game_path);
+
+    // Create action "add-game-path" to switch to a game folder that wasn't used before
+    let action_add_game_path = SimpleAction::new("add-game-path", None);
+
+    action_add_game_path.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        model,
+        move |_, _| {
+            let file_dialog = FileDialog::builder()
+                .accept_label("Open")
+                .title("Open the game directory")
+                .build();
+
+            file_dialog.select_folder(
+                Some(&window),
+                None::<&Cancellable>,
+                clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    model,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|f| f.path()) {
+                            switch_game_path(&window, &model, path);
+                        }
+                    }
+                )
+            );
+        }
+    ));
+
+    window.add_action(&action_add_game_path);
+
+    // Offer to reset the outdated backups after a game update
+    banner.connect_button_clicked(clone!(
+        #[weak]
+        window,
+        move |banner| {
+            reset_outdated_backups(&window, banner);
+        }
+    ));
+
+    // Create action "check-game-update" to compare the game files against the stored fingerprint
+    let action_check_game_update = SimpleAction::new("check-game-update", None);
+
+    action_check_game_update.connect_activate(clone!(
+        #[weak]
+        banner,
+        #[weak]
+        injector_button,
+        move |_, _| {
+            // The mod injector modifies the game files itself
+            if !injector_button.is_sensitive() {
+                return;
+            }
+
             let (tx, rx) = mpsc::channel();
 
-            // Run injector
             thread::spawn(move || {
-                tx.send(injector::run()).unwrap();
+                tx.send(game_update::has_changed()).unwrap();
             });
 
             MainContext::default().spawn_local(clone!(
                 #[weak]
-                window,
+                banner,
                 async move {
-                    if let Ok(success) = rx.recv() {
-                        if !success {
-                            // Create error dialog
-                            let err_dialog = AlertDialog::builder()
-                                .heading("Couldn't find default terminal.")
-                                .body("Set it using the $TERMINAL environment variable.")
-                                .default_response("ok")
-                                .close_response("ok")
-                                .build();
-
-                            err_dialog.add_responses(&[("ok", "_Ok")]);
-
-                            // WORKAROUND: AlertDialog's close response doesn't work
-                            err_dialog.connect_destroy(|dialog| {
-                                dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
-                            });
-
-                            let signal = err_dialog.connect_response(
-                                None,
-                                clone!(
-                                    #[weak]
-                                    window,
-                                    move |_, _| {
-                                        // Re-enable parent
-                                        window.set_sensitive(true);
-                                    }
-                                )
-                            );
-
-                            // WORKAROUND: AlertDialog's close response doesn't work
-                            err_dialog.connect_response(None, move |d, _| {
-                                d.block_signal(&signal);
-                            });
-
-                            err_dialog.present(Some(&window));
-                        }
-                        else {
-                            // Re-enable parent window
-                            window.set_sensitive(true);
-                        }
+                    if let Ok(changed) = rx.recv() {
+                        banner.set_revealed(changed);
                     }
                 }
             ));
         }
     ));
 
+    window.add_action(&action_check_game_update);
+
     // Init advanced options button
     let advanced_button = builder.object::<Button>("AdvancedOptions").unwrap();
 
@@ -155,13 +357,8 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
                 }
             ));
 
-            let settings_path = crate::GAME_PATH
-                .get()
-                .unwrap()
-                .join("EternalModInjector Settings.txt");
-
             // Check if settings file exists
-            if !settings_path.is_file() {
+            if !injector_settings::settings_path().is_file() {
                 // Create warning dialog
                 let warning_dialog = AlertDialog::builder()
                     .heading("Mod injector settings file not found.")
@@ -206,6 +403,193 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
         }
     ));
 
+    // Create action "show-conflicts" to show the files modified by more than one enabled mod
+    let action_show_conflicts = SimpleAction::new("show-conflicts", None);
+
+    action_show_conflicts.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        model,
+        move |_, _| {
+            // Disable main window
+            window.set_sensitive(false);
+
+            // Create conflicts window
+            let conflicts_window = conflicts_window::create(&window, &model);
+
+            // Re-enable main window on close
+            conflicts_window.connect_destroy(clone!(
+                #[weak]
+                window,
+                move |_| {
+                    window.set_sensitive(true);
+                }
+            ));
+
+            // Show conflicts window
+            conflicts_window.present();
+        }
+    ));
+
+    window.add_action(&action_show_conflicts);
+
+    // Create action "edit-load-order" to change the load priority of the enabled mods
+    let action_edit_load_order = SimpleAction::new("edit-load-order", None);
+
+    action_edit_load_order.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        model,
+        move |_, _| {
+            // Disable main window
+            window.set_sensitive(false);
+
+            // Create load order window
+            let load_order_window = load_order_window::create(&window, &model);
+
+            // Re-enable main window on close
+            load_order_window.connect_destroy(clone!(
+                #[weak]
+                window,
+                move |_| {
+                    window.set_sensitive(true);
+                }
+            ));
+
+            // Show load order window
+            load_order_window.present();
+        }
+    ));
+
+    window.add_action(&action_edit_load_order);
+
+    // Create action "manage-profiles" to save and apply named sets of enabled mods
+    let action_manage_profiles = SimpleAction::new("manage-profiles", None);
+
+    action_manage_profiles.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            // Disable main window
+            window.set_sensitive(false);
+
+            // Create profiles window
+            let profiles_window = profiles_window::create(&window);
+
+            // Re-enable main window on close
+            profiles_window.connect_destroy(clone!(
+                #[weak]
+                window,
+                move |_| {
+                    window.set_sensitive(true);
+                }
+            ));
+
+            // Show profiles window
+            profiles_window.present();
+        }
+    ));
+
+    window.add_action(&action_manage_profiles);
+
+    // Create action "export-modpack" to bundle the enabled mods and injector settings
+    let action_export_modpack = SimpleAction::new("export-modpack", None);
+
+    action_export_modpack.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            export_modpack(&window);
+        }
+    ));
+
+    window.add_action(&action_export_modpack);
+
+    // Create action "import-modpack" to install a modpack and enable only its mods
+    let action_import_modpack = SimpleAction::new("import-modpack", None);
+
+    action_import_modpack.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            import_modpack(&window);
+        }
+    ));
+
+    window.add_action(&action_import_modpack);
+
+    // Create action "verify-game-files" to compare the game files against the recorded hashes
+    let action_verify_game_files = SimpleAction::new("verify-game-files", None);
+
+    action_verify_game_files.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            verify_game_files(&window);
+        }
+    ));
+
+    window.add_action(&action_verify_game_files);
+
+    // Create action "record-game-files" to record the hashes of the pristine game files
+    let action_record_game_files = SimpleAction::new("record-game-files", None);
+
+    action_record_game_files.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            record_game_files(&window);
+        }
+    ));
+
+    window.add_action(&action_record_game_files);
+
+    #[cfg(target_os = "linux")]
+    {
+        // Add modding tools section to main menu, since they're only downloaded on Linux
+        let tools_section = Menu::new();
+        tools_section.append(
+            Some("Check for modding tools updates"),
+            Some("win.update-modding-tools")
+        );
+        tools_section.append(
+            Some("Restore previous modding tools"),
+            Some("win.roll-back-modding-tools")
+        );
+        builder
+            .object::<Menu>("MainMenu")
+            .unwrap()
+            .append_section(None, &tools_section);
+
+        // Create action "update-modding-tools" to look for a newer release of the modding tools
+        let action_update_modding_tools = SimpleAction::new("update-modding-tools", None);
+
+        action_update_modding_tools.connect_activate(clone!(
+            #[weak]
+            window,
+            move |_, _| {
+                check_modding_tools_update(&window, true);
+            }
+        ));
+
+        window.add_action(&action_update_modding_tools);
+
+        // Create action "roll-back-modding-tools" to restore the tools from before the latest update
+        let action_roll_back_modding_tools = SimpleAction::new("roll-back-modding-tools", None);
+
+        action_roll_back_modding_tools.connect_activate(clone!(
+            #[weak]
+            window,
+            move |_, _| {
+                roll_back_modding_tools(&window);
+            }
+        ));
+
+        window.add_action(&action_roll_back_modding_tools);
+    }
+
     // Get listbox from builder
     let listbox = builder.object::<ListBox>("ModList").unwrap();
 
@@ -262,6 +646,16 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
         // Set online safety message
         let online_safety_message = builder.object::<Label>("ModOnlineSafety").unwrap();
         online_safety_message.set_markup(&mod_data.online_safety_message().unwrap());
+
+        // Set offending files for online-unsafe mods
+        let online_safety_details = mod_data.online_safety_details().unwrap_or_default();
+        let online_safety_details_label = builder.object::<Label>("ModOnlineSafetyDetails").unwrap();
+        online_safety_details_label.set_label(&online_safety_details);
+
+        let online_safety_details_scrolled = builder
+            .object::<ScrolledWindow>("ModOnlineSafetyDetailsScrolled")
+            .unwrap();
+        online_safety_details_scrolled.set_visible(!online_safety_details.is_empty());
     });
 
     // Implement drag and drop
@@ -276,27 +670,13 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
         if let Ok(files) = v.get::<FileList>() {
             // Iterate through files
             for path in files.files().iter().filter_map(|f| f.path()) {
-                // Make sure file exists and is a zip
-                if !path.is_file() || path.extension().unwrap() != "zip" {
-                    return false;
-                }
-
-                let mods_folder = crate::GAME_PATH.get().unwrap().join("Mods");
-                let new_path = mods_folder.join(path.file_name().unwrap());
-
-                // Check if it's already in the target folder
-                if path
-                    .parent()
-                    .unwrap_or_else(|| Path::new(""))
-                    .canonicalize()
-                    .unwrap()
-                    == mods_folder.canonicalize().unwrap()
-                {
+                // Make sure file is a zip that isn't installed yet
+                if mods::install_path(&path).is_none() {
                     return false;
                 }
 
                 // Copy file to Mods folder
-                thread::spawn(move || fs::copy(&path, &new_path));
+                thread::spawn(move || mods::install(&path));
             }
 
             return true;
@@ -310,81 +690,146 @@ pub fn create(app: &Application, model: &Model) -> ApplicationWindow {
     window
 }
 
-// Get DOOM Eternal path
-pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model: &Model) {
-    // Get from arguments
-    if !files.is_empty() {
-        let path = files[0].path().unwrap();
+// Get a file filter for zip files
+fn zip_file_filters() -> ListStore {
+    let filter = FileFilter::new();
+    filter.set_name(Some("Zip files"));
+    filter.add_pattern("*.zip");
 
-        if path.is_dir() && path.join("DOOMEternalx64vk.exe").is_file() {
-            // Set game path
-            crate::GAME_PATH.set(path).unwrap();
-            save_game_path();
+    let filters = ListStore::new::<FileFilter>();
+    filters.append(&filter);
+    filters
+}
 
-            // Check modding tools
-            check_modding_tools(parent_window);
+// Export the enabled mods and injector settings into a modpack
+fn export_modpack(window: &ApplicationWindow) {
+    // Create file dialog to select the modpack path
+    let file_dialog = FileDialog::builder()
+        .accept_label("Export")
+        .title("Export modpack")
+        .initial_name("modpack.zip")
+        .filters(&zip_file_filters())
+        .build();
 
-            // Init watcher
-            init_watcher(model);
-            return;
-        }
-    }
+    file_dialog.save(
+        Some(window),
+        None::<&Cancellable>,
+        clone!(
+            #[weak]
+            window,
+            move |result| {
+                let path = match result.ok().and_then(|f| f.path()) {
+                    Some(p) => p,
+                    None => return
+                };
 
-    // Get from config file
-    let keyfile = KeyFile::new();
-    let mut config_dirs = vec![glib::user_config_dir()];
-    config_dirs.append(&mut glib::system_config_dirs());
+                // Disable window
+                window.set_sensitive(false);
 
-    if keyfile
-        .load_from_dirs("EternalModManager/config", &config_dirs, KeyFileFlags::NONE)
-        .is_ok()
-    {
-        if let Ok(path) = keyfile.string("settings", "game-path") {
-            let path_buf = PathBuf::from(path.to_string());
+                let (tx, rx) = mpsc::channel();
 
-            if path_buf.is_dir() && path_buf.join("DOOMEternalx64vk.exe").is_file() {
-                // Set game path
-                crate::GAME_PATH.set(path_buf).unwrap();
-                save_game_path();
+                // Write modpack
+                thread::spawn(move || {
+                    tx.send(modpack::export(&path)).unwrap();
+                });
 
-                // Check modding tools
-                check_modding_tools(parent_window);
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    window,
+                    async move {
+                        if let Ok(result) = rx.recv() {
+                            window.set_sensitive(true);
 
-                // Init watcher
-                init_watcher(model);
-                return;
+                            match result {
+                                Ok(count) => show_message(
+                                    &window,
+                                    "Modpack exported.",
+                                    &format!("{} enabled mods were added to the modpack.", count)
+                                ),
+                                Err(e) => show_message(&window, "Failed to export modpack.", &e.to_string())
+                            }
+                        }
+                    }
+                ));
             }
-        }
-    }
-
-    // Get from current directory
-    let current_directory = env::current_dir().unwrap();
+        )
+    );
+}
 
-    if current_directory.join("DOOMEternalx64vk.exe").is_file() {
-        // Set game path
-        crate::GAME_PATH.set(current_directory).unwrap();
-        save_game_path();
+// Import a modpack
+fn import_modpack(window: &ApplicationWindow) {
+    // Create file dialog to select the modpack
+    let file_dialog = FileDialog::builder()
+        .accept_label("Import")
+        .title("Import modpack")
+        .filters(&zip_file_filters())
+        .build();
 
-        // Check modding tools
-        check_modding_tools(parent_window);
+    file_dialog.open(
+        Some(window),
+        None::<&Cancellable>,
+        clone!(
+            #[weak]
+            window,
+            move |result| {
+                let path = match result.ok().and_then(|f| f.path()) {
+                    Some(p) => p,
+                    None => return
+                };
+
+                // Disable window
+                window.set_sensitive(false);
+
+                let (tx, rx) = mpsc::channel();
+
+                // Check for installed mods the modpack would replace
+                thread::spawn(clone!(
+                    #[strong]
+                    path,
+                    move || {
+                        tx.send(modpack::replaced_mods(&path)).unwrap();
+                    }
+                ));
 
-        // Init watcher
-        init_watcher(model);
-        return;
-    }
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    window,
+                    async move {
+                        if let Ok(result) = rx.recv() {
+                            window.set_sensitive(true);
 
-    // Get from user input
-    // Disable parent window
-    parent_window.set_sensitive(false);
+                            match result {
+                                Ok(replaced) if replaced.is_empty() => install_modpack(&window, path, false),
+                                Ok(replaced) => ask_replace_mods(&window, path, &replaced),
+                                Err(e) => show_message(
+                                    &window,
+                                    "Failed to import modpack.",
+                                    &format!("{}\n\nNo changes were made.", e)
+                                )
+                            }
+                        }
+                    }
+                ));
+            }
+        )
+    );
+}
 
-    // Create dialog
+// Ask whether to replace installed mods that have the same name as one of the modpack's
+fn ask_replace_mods(window: &ApplicationWindow, path: PathBuf, replaced: &[String]) {
     let dialog = AlertDialog::builder()
-        .heading("Open the DOOM Eternal game directory.")
-        .default_response("ok")
-        .close_response("ok")
+        .heading("Replace installed mods?")
+        .body(format!(
+            "The modpack has different versions of the following installed mods:\n{}\n\nReplace them with \
+             the modpack's versions, or keep the installed ones?",
+            replaced.join("\n")
+        ))
+        .default_response("keep")
+        .close_response("cancel")
         .build();
 
-    dialog.add_responses(&[("ok", "_Ok")]);
+    dialog.add_responses(&[("cancel", "_Cancel"), ("keep", "_Keep"), ("replace", "_Replace")]);
+    dialog.set_response_appearance("replace", ResponseAppearance::Destructive);
 
     // WORKAROUND: AlertDialog's close response doesn't work
     dialog.connect_destroy(|dialog| {
@@ -395,12 +840,762 @@ pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model
         None,
         clone!(
             #[weak]
-            parent_window,
-            #[weak]
-            model,
-            move |_, _| {
-                // Create file dialog to select folder
-                let file_dialog = FileDialog::builder()
+            window,
+            move |_, result| {
+                match result {
+                    "keep" => install_modpack(&window, path.clone(), false),
+                    "replace" => install_modpack(&window, path.clone(), true),
+                    _ => {}
+                }
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(window));
+}
+
+// Install a modpack in the background and show the result
+fn install_modpack(window: &ApplicationWindow, path: PathBuf, replace: bool) {
+    // Disable window
+    window.set_sensitive(false);
+
+    let (tx, rx) = mpsc::channel();
+
+    // Install modpack
+    thread::spawn(move || {
+        tx.send(modpack::import(&path, replace)).unwrap();
+    });
+
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        window,
+        async move {
+            if let Ok(result) = rx.recv() {
+                window.set_sensitive(true);
+
+                let report = match result {
+                    Ok(r) => r,
+                    Err(e) => {
+                        show_message(
+                            &window,
+                            "Failed to import modpack.",
+                            &format!("{}\n\nNo changes were made.", e)
+                        );
+                        return;
+                    }
+                };
+
+                let mut body = format!(
+                    "{} mods were installed and {} mods were disabled.",
+                    report.installed, report.disabled
+                );
+
+                if !report.kept.is_empty() {
+                    body += &format!(
+                        "\n\nThe following installed mods were kept instead of the modpack's versions:\n{}",
+                        report.kept.join("\n")
+                    );
+                }
+
+                if !report.failed.is_empty() {
+                    body += &format!(
+                        "\n\nThe following mods couldn't be moved:\n{}",
+                        report.failed.join("\n")
+                    );
+                }
+
+                if !report.skipped_settings.is_empty() {
+                    body += &format!(
+                        "\n\nThe following injector settings can't be changed and were skipped:\n{}",
+                        report.skipped_settings.join("\n")
+                    );
+                }
+
+                if !report.settings_saved {
+                    body += "\n\nThe injector settings couldn't be saved.";
+                }
+
+                show_message(&window, "Modpack imported.", &body);
+            }
+        }
+    ));
+}
+
+// Event sent by a game file integrity task
+enum IntegrityEvent<T> {
+    Progress(usize, usize),
+    Done(T)
+}
+
+// Run a game file integrity task in the background, showing its progress
+fn run_integrity_task<T: Send + 'static>(
+    window: &ApplicationWindow, heading: &str,
+    task: impl FnOnce(&dyn Fn(usize, usize)) -> T + Send + 'static,
+    on_done: impl FnOnce(&ApplicationWindow, T) + 'static
+) {
+    // Create progress dialog
+    let progress_bar = ProgressBar::builder().show_text(true).build();
+
+    let dialog = AlertDialog::builder()
+        .heading(heading)
+        .body("This might take a while depending on the speed of your disk, so please be patient.")
+        .extra_child(&progress_bar)
+        .can_close(false)
+        .build();
+
+    dialog.add_responses(&[("cancel", "_Cancel")]);
+
+    // Stop the task after the current file
+    dialog.connect_response(Some("cancel"), |_, _| {
+        integrity::cancel();
+    });
+
+    dialog.present(Some(window));
+
+    // Run task
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let progress_tx = tx.clone();
+        let result = task(&move |done, total| {
+            let _ = progress_tx.send(IntegrityEvent::Progress(done, total));
+        });
+
+        let _ = tx.send(IntegrityEvent::Done(result));
+    });
+
+    // Poll task events
+    let mut on_done = Some(on_done);
+
+    glib::timeout_add_local(
+        Duration::from_millis(100),
+        clone!(
+            #[weak]
+            window,
+            #[upgrade_or]
+            ControlFlow::Break,
+            move || loop {
+                match rx.try_recv() {
+                    Ok(IntegrityEvent::Progress(done, total)) => {
+                        if total > 0 {
+                            progress_bar.set_fraction(done as f64 / total as f64);
+                        }
+
+                        progress_bar.set_text(Some(&format!("{} of {} files", done, total)));
+                    },
+                    Ok(IntegrityEvent::Done(result)) => {
+                        dialog.force_close();
+
+                        if let Some(on_done) = on_done.take() {
+                            on_done(&window, result);
+                        }
+
+                        return ControlFlow::Break;
+                    },
+                    Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => {
+                        dialog.force_close();
+                        return ControlFlow::Break;
+                    }
+                }
+            }
+        )
+    );
+}
+
+// Record the hashes of the pristine game files after confirmation
+fn record_game_files(window: &ApplicationWindow) {
+    if integrity::is_recording() {
+        show_message(
+            window,
+            "Game file hashes are already being recorded.",
+            "Try again once EternalModManager finishes recording them."
+        );
+        return;
+    }
+
+    let dialog = AlertDialog::builder()
+        .heading("Record game file hashes?")
+        .body(
+            "The current game files will be used to detect modded or corrupted files later. The mod \
+             injector's backups are used instead when they exist.\n\nFor best results, verify the game \
+             files in Steam before recording."
+        )
+        .default_response("no")
+        .close_response("no")
+        .build();
+
+    dialog.add_responses(&[("yes", "_Record"), ("no", "_Cancel")]);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            window,
+            move |_, result| {
+                if result != "yes" {
+                    return;
+                }
+
+                run_integrity_task(
+                    &window,
+                    "Recording game file hashes...",
+                    |progress| integrity::record(progress),
+                    |window, result| match result {
+                        Ok(count) => show_message(
+                            window,
+                            "Game file hashes recorded.",
+                            &format!("{} game files were recorded.", count)
+                        ),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                        Err(e) => show_message(window, "Failed to record game file hashes.", &e.to_string())
+                    }
+                );
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(window));
+}
+
+// Verify the game files against the recorded hashes
+fn verify_game_files(window: &ApplicationWindow) {
+    if integrity::is_recording() {
+        show_message(
+            window,
+            "Game file hashes are still being recorded.",
+            "Try again once EternalModManager finishes recording them."
+        );
+        return;
+    }
+
+    if !integrity::has_manifest() {
+        record_game_files(window);
+        return;
+    }
+
+    run_integrity_task(
+        window,
+        "Verifying game files...",
+        |progress| integrity::verify(progress),
+        |window, result| match result {
+            Ok(report) => show_verify_report(window, &report),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => show_message(window, "Failed to verify game files.", &e.to_string())
+        }
+    );
+}
+
+// Show the changed game files, offering to restore them from the backups
+fn show_verify_report(window: &ApplicationWindow, report: &VerifyReport) {
+    if report.changed.is_empty() {
+        show_message(
+            window,
+            "Game files verified.",
+            &format!("All {} game files match the recorded hashes.", report.checked)
+        );
+        return;
+    }
+
+    // List changed files by state
+    let mut body = Vec::new();
+
+    for (state, description) in [
+        (FileState::Modded, "Modded, can be restored from backups"),
+        (
+            FileState::Corrupted,
+            "Modified or corrupted, without a valid backup"
+        ),
+        (FileState::Missing, "Missing")
+    ] {
+        let mut names = report
+            .changed
+            .iter()
+            .filter(|f| f.state == state)
+            .map(|f| f.name.clone())
+            .collect::<Vec<String>>();
+
+        if names.is_empty() {
+            continue;
+        }
+
+        let count = names.len();
+
+        if count > 10 {
+            names.truncate(10);
+            names.push(format!("...and {} more.", count - 10));
+        }
+
+        body.push(format!("{} ({}):\n{}", description, count, names.join("\n")));
+    }
+
+    let restorable = report.restorable();
+
+    if restorable.len() < report.changed.len() {
+        body.push(String::from(
+            "Files without a valid backup can only be fixed by verifying the game files in Steam."
+        ));
+    }
+
+    let dialog = AlertDialog::builder()
+        .heading(format!(
+            "{} of {} game files don't match the recorded hashes.",
+            report.changed.len(),
+            report.checked
+        ))
+        .body(body.join("\n\n"))
+        .default_response("close")
+        .close_response("close")
+        .build();
+
+    if restorable.is_empty() {
+        dialog.add_responses(&[("close", "_Close")]);
+        dialog.present(Some(window));
+        return;
+    }
+
+    dialog.add_responses(&[("restore", "_Restore from backups"), ("close", "_Close")]);
+    dialog.set_response_appearance("restore", ResponseAppearance::Suggested);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            window,
+            move |_, result| {
+                if result != "restore" {
+                    return;
+                }
+
+                let restorable = restorable.clone();
+                let count = restorable.len();
+
+                run_integrity_task(
+                    &window,
+                    "Restoring game files...",
+                    move |progress| {
+                        let failed = integrity::restore(&restorable, progress);
+
+                        // Fingerprint the restored game files
+                        game_update::record();
+                        failed
+                    },
+                    move |window, failed| {
+                        if failed.is_empty() {
+                            show_message(
+                                window,
+                                "Game files restored.",
+                                &format!("{} game files were restored from the backups.", count)
+                            );
+                        }
+                        else {
+                            show_message(
+                                window,
+                                "Some game files couldn't be restored.",
+                                &format!("The following files couldn't be restored:\n{}", failed.join("\n"))
+                            );
+                        }
+                    }
+                );
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(window));
+}
+
+// Delete the backups made before a game update, or have the injector do it on its next run
+fn reset_outdated_backups(window: &ApplicationWindow, banner: &Banner) {
+    let dialog = AlertDialog::builder()
+        .heading("DOOM Eternal was updated.")
+        .body(
+            "The mod injector's backups still hold the game files from before the update, and will \
+             overwrite the updated files the next time mods are injected.\n\nReset the backups now, or let \
+             the mod injector reset them the next time it runs. Either way, verify the game files in Steam \
+             before injecting mods."
+        )
+        .default_response("later")
+        .close_response("later")
+        .build();
+
+    dialog.add_responses(&[
+        ("now", "Reset _now"),
+        ("next", "Reset on next _injection"),
+        ("later", "_Later")
+    ]);
+    dialog.set_response_appearance("now", ResponseAppearance::Destructive);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            window,
+            #[weak]
+            banner,
+            move |_, result| {
+                match result {
+                    "now" => {
+                        // Disable parent window
+                        window.set_sensitive(false);
+
+                        let (tx, rx) = mpsc::channel();
+
+                        // Delete backups, then fingerprint the updated game
+                        thread::spawn(move || {
+                            let backups_deleted = backups::delete();
+                            game_update::record();
+                            tx.send(backups_deleted).unwrap();
+                        });
+
+                        MainContext::default().spawn_local(clone!(
+                            #[weak]
+                            window,
+                            #[weak]
+                            banner,
+                            async move {
+                                if let Ok(backups_deleted) = rx.recv() {
+                                    window.set_sensitive(true);
+                                    banner.set_revealed(false);
+
+                                    show_message(
+                                        &window,
+                                        "Done.",
+                                        &format!(
+                                            "{} backups were deleted. Verify the game files in Steam before \
+                                             injecting mods.",
+                                            backups_deleted
+                                        )
+                                    );
+                                }
+                            }
+                        ));
+                    },
+                    "next" => {
+                        if !injector_settings::write_settings(&[(
+                            String::from("RESET_BACKUPS"),
+                            String::from("1")
+                        )]) {
+                            show_message(
+                                &window,
+                                "Failed to change the mod injector settings.",
+                                "Make sure the modding tools are installed in the game folder."
+                            );
+                            return;
+                        }
+
+                        banner.set_revealed(false);
+                        thread::spawn(game_update::record);
+                    },
+                    _ => {}
+                }
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(window));
+}
+
+// Show a message dialog
+pub fn show_message(window: &ApplicationWindow, heading: &str, body: &str) {
+    let dialog = AlertDialog::builder()
+        .heading(heading)
+        .body(body)
+        .default_response("ok")
+        .close_response("ok")
+        .build();
+
+    dialog.add_responses(&[("ok", "_Ok")]);
+    dialog.present(Some(window));
+}
+
+// Run the mod injector
+fn run_injector(
+    window: &ApplicationWindow, model: &Model, banner: &Banner, console: &InjectorConsole,
+    controls: &[Widget]
+) {
+    // Disable mod controls while the injector runs
+    for control in controls {
+        control.set_sensitive(false);
+    }
+
+    let disabled_controls = controls.to_vec();
+
+    let result = console.run(
+        model,
+        clone!(
+            #[weak]
+            banner,
+            move |_| {
+                // Re-enable mod controls
+                for control in &disabled_controls {
+                    control.set_sensitive(true);
+                }
+
+                // The injector modified the game files, so fingerprint them again
+                // unless an update is still waiting to be handled
+                if !banner.is_revealed() {
+                    thread::spawn(game_update::record);
+                }
+            }
+        )
+    );
+
+    if let Err(e) = result {
+        // Re-enable mod controls
+        for control in controls {
+            control.set_sensitive(true);
+        }
+
+        show_message(
+            window,
+            "Failed to run the mod injector.",
+            &format!(
+                "{}\n\nMake sure the modding tools are installed in the game folder.",
+                e
+            )
+        );
+    }
+}
+
+// Get DOOM Eternal path
+pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model: &Model) {
+    // Get from arguments
+    if !files.is_empty() {
+        let path = files[0].path().unwrap();
+
+        if mods::is_game_folder(&path) {
+            set_game_path(parent_window, model, path);
+            return;
+        }
+    }
+
+    // Get from config file
+    if let Ok(path) = config::load().string("settings", "game-path") {
+        let path_buf = PathBuf::from(path.to_string());
+
+        if mods::is_game_folder(&path_buf) {
+            set_game_path(parent_window, model, path_buf);
+            return;
+        }
+    }
+
+    // Get from current directory
+    let current_directory = env::current_dir().unwrap();
+
+    if mods::is_game_folder(&current_directory) {
+        set_game_path(parent_window, model, current_directory);
+        return;
+    }
+
+    // Get from Steam libraries
+    let mut steam_game_folders = steam::find_game_folders();
+
+    match steam_game_folders.len() {
+        0 => ask_game_path(parent_window, model),
+        1 => set_game_path(parent_window, model, steam_game_folders.remove(0)),
+        _ => choose_steam_game_path(parent_window, model, steam_game_folders)
+    }
+}
+
+// Set the game path and start managing its mods
+fn set_game_path(parent_window: &ApplicationWindow, model: &Model, path: PathBuf) {
+    // Set game path
+    crate::set_game_path(path.clone());
+    save_game_path(parent_window);
+
+    // Show the new game path in the game folder switcher
+    if let Some(action) = parent_window
+        .lookup_action("switch-game-path")
+        .and_downcast::<SimpleAction>()
+    {
+        action.set_state(&path.to_string_lossy().to_variant());
+    }
+
+    // Check modding tools
+    check_modding_tools(parent_window);
+
+    // Init watcher
+    init_watcher(parent_window, model);
+}
+
+// Switch to another game folder while running
+fn switch_game_path(window: &ApplicationWindow, model: &Model, path: PathBuf) {
+    if crate::try_game_path().as_ref() == Some(&path) {
+        return;
+    }
+
+    if !mods::is_game_folder(&path) {
+        show_message(
+            window,
+            "Can't switch to this game directory.",
+            &format!("\"{}\" is not a DOOM Eternal game directory.", path.display())
+        );
+        return;
+    }
+
+    // The hashes would be recorded for the wrong game folder
+    if integrity::is_recording() {
+        show_message(
+            window,
+            "Game file hashes are being recorded.",
+            "Try again once EternalModManager finishes recording them."
+        );
+        return;
+    }
+
+    set_game_path(window, model, path);
+}
+
+// Create the game folder switcher menu from the used game folders
+fn game_path_menu() -> Menu {
+    let menu = Menu::new();
+
+    let folders_section = Menu::new();
+
+    for path in config::game_paths().iter().filter(|p| mods::is_game_folder(p)) {
+        let path = path.to_string_lossy();
+        let item = MenuItem::new(Some(&path), None);
+        item.set_action_and_target_value(Some("win.switch-game-path"), Some(&path.to_variant()));
+        folders_section.append_item(&item);
+    }
+
+    menu.append_section(None, &folders_section);
+
+    let add_section = Menu::new();
+    add_section.append(Some("Add game folder..."), Some("win.add-game-path"));
+    menu.append_section(None, &add_section);
+
+    menu
+}
+
+// Let the user choose between the DOOM Eternal installs found in the Steam libraries
+fn choose_steam_game_path(parent_window: &ApplicationWindow, model: &Model, game_folders: Vec<PathBuf>) {
+    // Disable parent window
+    parent_window.set_sensitive(false);
+
+    // List installs
+    let folder_names = game_folders
+        .iter()
+        .map(|f| f.to_string_lossy().into_owned())
+        .collect::<Vec<String>>();
+    let folder_list = DropDown::from_strings(&folder_names.iter().map(String::as_str).collect::<Vec<&str>>());
+
+    // Create dialog
+    let dialog = AlertDialog::builder()
+        .heading("Several DOOM Eternal installs were found.")
+        .body("Choose the game directory to manage mods for.")
+        .extra_child(&folder_list)
+        .default_response("use")
+        .close_response("other")
+        .build();
+
+    dialog.add_responses(&[
+        ("use", "_Use this directory"),
+        ("other", "_Open another directory")
+    ]);
+    dialog.set_response_appearance("use", ResponseAppearance::Suggested);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            parent_window,
+            #[weak]
+            model,
+            #[weak]
+            folder_list,
+            move |_, result| {
+                if result != "use" {
+                    ask_game_path(&parent_window, &model);
+                    return;
+                }
+
+                // Re-enable parent window
+                parent_window.set_sensitive(true);
+
+                let path = game_folders[folder_list.selected() as usize].clone();
+                set_game_path(&parent_window, &model, path);
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(parent_window));
+}
+
+// Ask the user for the game directory
+fn ask_game_path(parent_window: &ApplicationWindow, model: &Model) {
+    // Disable parent window
+    parent_window.set_sensitive(false);
+
+    // Create dialog
+    let dialog = AlertDialog::builder()
+        .heading("Open the DOOM Eternal game directory.")
+        .default_response("ok")
+        .close_response("ok")
+        .build();
+
+    dialog.add_responses(&[("ok", "_Ok")]);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            parent_window,
+            #[weak]
+            model,
+            move |_, _| {
+                // Create file dialog to select folder
+                let file_dialog = FileDialog::builder()
                     .accept_label("Open")
                     .title("Open the game directory")
                     .build();
@@ -418,21 +1613,13 @@ pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model
                             if let Ok(file) = result {
                                 let path = file.path().unwrap();
 
-                                if path.is_dir() && path.join("DOOMEternalx64vk.exe").is_file() {
-                                    // Set game path
-                                    crate::GAME_PATH.set(path).unwrap();
-                                    save_game_path();
-
-                                    // Check modding tools
-                                    check_modding_tools(&parent_window);
-
-                                    // Init watcher
-                                    init_watcher(&model);
+                                if mods::is_game_folder(&path) {
+                                    set_game_path(&parent_window, &model, path);
                                 }
                             }
 
                             // Make sure game path is set now
-                            if crate::GAME_PATH.get().is_none() {
+                            if crate::try_game_path().is_none() {
                                 // Create error dialog
                                 let err_dialog = AlertDialog::builder()
                                     .heading("Can't find the game directory.")
@@ -448,35 +1635,486 @@ pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model
                                     dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
                                 });
 
-                                let signal = err_dialog.connect_response(
-                                    None,
-                                    clone!(
-                                        #[weak]
-                                        parent_window,
-                                        move |_, _| {
-                                            // Exit
-                                            parent_window.close();
-                                        }
-                                    )
-                                );
+                                let signal = err_dialog.connect_response(
+                                    None,
+                                    clone!(
+                                        #[weak]
+                                        parent_window,
+                                        move |_, _| {
+                                            // Exit
+                                            parent_window.close();
+                                        }
+                                    )
+                                );
+
+                                // WORKAROUND: AlertDialog's close response doesn't work
+                                err_dialog.connect_response(None, move |d, _| {
+                                    d.block_signal(&signal);
+                                });
+
+                                err_dialog.present(Some(&parent_window));
+                            }
+                            else {
+                                // Re-enable parent window
+                                parent_window.set_sensitive(true);
+                            }
+                        }
+                    )
+                );
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(parent_window));
+}
+
+// Report an invalid online safety rules file
+pub fn show_rules_error(parent_window: &ApplicationWindow, err: &RulesError) {
+    // Create warning dialog
+    let warning_dialog = AlertDialog::builder()
+        .heading("Failed to load the online safety rules.")
+        .body(format!(
+            "{}\n\nThe default rules will be used instead. Fix or remove \"{}\" to stop seeing this message.",
+            err,
+            online_safety::user_rules_path().display()
+        ))
+        .default_response("ok")
+        .close_response("ok")
+        .build();
+
+    warning_dialog.add_responses(&[("ok", "_Ok")]);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    warning_dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    warning_dialog.present(Some(parent_window));
+}
+
+// Save game path to config file
+fn save_game_path(window: &ApplicationWindow) {
+    // Load config file, keeping other settings
+    let keyfile = config::load_user();
+    keyfile.set_string(
+        "settings",
+        "game-path",
+        &crate::game_path().into_os_string().into_string().unwrap()
+    );
+
+    // Remember game path to switch back to it later
+    config::add_game_path(&keyfile, &crate::game_path());
+
+    // Save config file
+    config::save(&keyfile);
+
+    // Record the game files the first time this game folder is used
+    if !integrity::has_manifest() && !integrity::is_recording() {
+        run_integrity_task(
+            window,
+            "Recording game file hashes...",
+            |progress| integrity::record(progress),
+            |window, result| match result {
+                Ok(_) => (),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => show_message(window, "Failed to record game file hashes.", &e.to_string())
+            }
+        );
+    }
+}
+
+#[cfg(target_os = "windows")]
+// Check for the modding tools on Windows
+fn check_modding_tools(parent_window: &ApplicationWindow) {
+    // Check if injector batch is present
+    if modding_tools::is_installed() {
+        return;
+    }
+
+    // Disable parent window
+    parent_window.set_sensitive(false);
+
+    // Create error dialog
+    let err_dialog = AlertDialog::builder()
+        .heading("Can't find EternalModInjector.bat.")
+        .body("Make sure that the modding tools are installed, or install them from a local archive.")
+        .default_response("ok")
+        .close_response("ok")
+        .build();
+
+    err_dialog.add_responses(&[("local", "Choose _Local Archive…"), ("ok", "_Ok")]);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    err_dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = err_dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            parent_window,
+            move |_, result| {
+                if result == "local" {
+                    choose_modding_tools_archive(&parent_window);
+                    return;
+                }
+
+                // Exit
+                parent_window.close();
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    err_dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    err_dialog.present(Some(parent_window));
+}
+
+#[cfg(target_os = "linux")]
+// Check for the modding tools on Linux (and download them)
+fn check_modding_tools(parent_window: &ApplicationWindow) {
+    // Look for updates if the tools are installed
+    if modding_tools::is_installed() {
+        check_modding_tools_update(parent_window, false);
+        return;
+    }
+
+    // Disable parent window
+    parent_window.set_sensitive(false);
+
+    // Create question dialog
+    let dialog = AlertDialog::builder()
+        .heading("Couldn't find the modding tools.")
+        .body("Do you want to download them? They can also be installed from a local archive.")
+        .default_response("yes")
+        .close_response("no")
+        .build();
+
+    dialog.add_responses(&[
+        ("yes", "_Yes"),
+        ("local", "Choose _Local Archive…"),
+        ("no", "_No")
+    ]);
+    dialog.set_response_appearance("yes", ResponseAppearance::Suggested);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            parent_window,
+            move |_, result| {
+                // Check user selection
+                match result {
+                    "no" => {
+                        // Exit
+                        parent_window.close();
+                    },
+                    "local" => choose_modding_tools_archive(&parent_window),
+                    _ => {
+                        // Download modding tools
+                        install_modding_tools(&parent_window, None, |parent_window, result| match result {
+                            // Ask for the modding tools again
+                            Err(ToolsError::Release(ReleaseError::Cancelled)) => {
+                                check_modding_tools(parent_window)
+                            },
+                            result => finish_modding_tools_install(
+                                parent_window,
+                                result.map(|_| ()),
+                                "Failed to download the modding tools."
+                            )
+                        });
+                    }
+                }
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    dialog.present(Some(parent_window));
+}
+
+// Install the modding tools from an archive the user chooses
+fn choose_modding_tools_archive(parent_window: &ApplicationWindow) {
+    // Create file dialog to select the archive
+    let file_dialog = FileDialog::builder()
+        .accept_label("Install")
+        .title(format!("Open {}", modding_tools::ARCHIVE_NAME))
+        .filters(&zip_file_filters())
+        .build();
+
+    file_dialog.open(
+        Some(parent_window),
+        None::<&Cancellable>,
+        clone!(
+            #[weak]
+            parent_window,
+            move |result| {
+                let path = match result.ok().and_then(|f| f.path()) {
+                    Some(p) => p,
+                    None => {
+                        // Ask for the modding tools again
+                        check_modding_tools(&parent_window);
+                        return;
+                    }
+                };
+
+                finish_modding_tools_install(
+                    &parent_window,
+                    modding_tools::install_archive(&path),
+                    "Failed to install the modding tools."
+                );
+            }
+        )
+    );
+}
+
+// Re-enable the window once the modding tools are installed, or ask for them again if they weren't
+fn finish_modding_tools_install(
+    parent_window: &ApplicationWindow, result: Result<(), ToolsError>, heading: &str
+) {
+    let error = match result {
+        Ok(()) => {
+            // Re-enable parent
+            parent_window.set_sensitive(true);
+            return;
+        },
+        Err(e) => e
+    };
+
+    // Create error dialog
+    let err_dialog = AlertDialog::builder()
+        .heading(heading)
+        .body(error.to_string())
+        .default_response("ok")
+        .close_response("ok")
+        .build();
+
+    err_dialog.add_responses(&[("ok", "_Ok")]);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    err_dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = err_dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            parent_window,
+            move |_, _| {
+                check_modding_tools(&parent_window);
+            }
+        )
+    );
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    err_dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
+
+    err_dialog.present(Some(parent_window));
+}
+
+#[cfg(target_os = "linux")]
+// Event sent while downloading the modding tools
+enum ToolsEvent {
+    ConfirmUnverified(String, mpsc::Sender<bool>),
+    Progress(u64, Option<u64>),
+    Extracting,
+    Done(Result<String, ToolsError>)
+}
+
+#[cfg(target_os = "linux")]
+// Download and install the modding tools in the background, or the latest release if none is given
+fn install_modding_tools(
+    parent_window: &ApplicationWindow, release: Option<Release>,
+    on_done: impl FnOnce(&ApplicationWindow, Result<String, ToolsError>) + 'static
+) {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc
+    };
+
+    // Create progress dialog
+    let progress_bar = ProgressBar::builder().show_text(true).build();
+    progress_bar.set_text(Some("Connecting..."));
+
+    let dialog = AlertDialog::builder()
+        .heading("Downloading the modding tools...")
+        .body("Cancelled downloads are resumed the next time the tools are downloaded.")
+        .extra_child(&progress_bar)
+        .can_close(false)
+        .build();
+
+    dialog.add_responses(&[("cancel", "_Cancel")]);
+
+    // Stop the download on cancel
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    dialog.connect_response(
+        None,
+        clone!(
+            #[strong]
+            cancel,
+            move |_, _| {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        )
+    );
+
+    dialog.present(Some(parent_window));
+
+    // Releases found here weren't shown to the user yet, so unverified ones need confirmation
+    let confirm_unverified = release.is_none();
+
+    // Download and install tools
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(clone!(
+        #[strong]
+        cancel,
+        move || {
+            let progress_tx = tx.clone();
+
+            let result = release
+                .map_or_else(modding_tools::release::latest, Ok)
+                .and_then(|release| {
+                    if confirm_unverified && !release.is_verified() {
+                        let (answer_tx, answer_rx) = mpsc::channel();
+                        let _ = tx.send(ToolsEvent::ConfirmUnverified(release.version.clone(), answer_tx));
+
+                        if answer_rx.recv() != Ok(true) {
+                            return Err(ReleaseError::Cancelled);
+                        }
+                    }
+
+                    modding_tools::release::download(
+                        &release,
+                        |done, total| {
+                            let _ = progress_tx.send(ToolsEvent::Progress(done, total));
+                        },
+                        &cancel
+                    )
+                    .map(|bytes| (release, bytes))
+                })
+                .map_err(ToolsError::Release)
+                .and_then(|(release, bytes)| {
+                    let _ = tx.send(ToolsEvent::Extracting);
+                    modding_tools::install(bytes, Some(&release.version))?;
+                    Ok(release.version)
+                });
+
+            let _ = tx.send(ToolsEvent::Done(result));
+        }
+    ));
 
-                                // WORKAROUND: AlertDialog's close response doesn't work
-                                err_dialog.connect_response(None, move |d, _| {
-                                    d.block_signal(&signal);
-                                });
+    // Poll download events
+    let mut on_done = Some(on_done);
 
-                                err_dialog.present(Some(&parent_window));
-                            }
-                            else {
-                                // Re-enable parent window
-                                parent_window.set_sensitive(true);
+    glib::timeout_add_local(
+        Duration::from_millis(100),
+        clone!(
+            #[weak]
+            parent_window,
+            #[upgrade_or]
+            ControlFlow::Break,
+            move || loop {
+                match rx.try_recv() {
+                    Ok(ToolsEvent::ConfirmUnverified(version, answer_tx)) => {
+                        confirm_unverified_download(&parent_window, &version, answer_tx);
+                    },
+                    Ok(ToolsEvent::Progress(done, total)) => {
+                        let done_mb = done as f64 / 1_000_000.0;
+
+                        match total {
+                            Some(total) if total > 0 => {
+                                progress_bar.set_fraction(done as f64 / total as f64);
+                                progress_bar.set_text(Some(&format!(
+                                    "{:.1} of {:.1} MB",
+                                    done_mb,
+                                    total as f64 / 1_000_000.0
+                                )));
+                            },
+                            _ => {
+                                progress_bar.pulse();
+                                progress_bar.set_text(Some(&format!("{:.1} MB", done_mb)));
                             }
                         }
-                    )
-                );
+                    },
+                    Ok(ToolsEvent::Extracting) => {
+                        // The tools can't be half-extracted
+                        dialog.set_response_enabled("cancel", false);
+                        progress_bar.set_fraction(1.0);
+                        progress_bar.set_text(Some("Extracting..."));
+                    },
+                    Ok(ToolsEvent::Done(result)) => {
+                        // The dialog closes itself when cancelled
+                        if !cancel.load(Ordering::Relaxed) {
+                            dialog.force_close();
+                        }
+
+                        if let Some(on_done) = on_done.take() {
+                            on_done(&parent_window, result);
+                        }
+
+                        return ControlFlow::Break;
+                    },
+                    Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => {
+                        dialog.force_close();
+                        return ControlFlow::Break;
+                    }
+                }
             }
         )
     );
+}
+
+#[cfg(target_os = "linux")]
+// Ask before downloading a release without a checksum, sending the answer back
+fn confirm_unverified_download(
+    parent_window: &ApplicationWindow, version: &str, answer_tx: mpsc::Sender<bool>
+) {
+    let dialog = AlertDialog::builder()
+        .heading("Download unverified modding tools?")
+        .body(format!(
+            "Version {} of the modding tools doesn't publish a checksum, so the download can't be verified \
+             before the tools are installed and run.",
+            version
+        ))
+        .default_response("cancel")
+        .close_response("cancel")
+        .build();
+
+    dialog.add_responses(&[("download", "_Download anyway"), ("cancel", "_Cancel")]);
+    dialog.set_response_appearance("download", ResponseAppearance::Destructive);
+
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
+
+    let signal = dialog.connect_response(None, move |_, result| {
+        let _ = answer_tx.send(result == "download");
+    });
 
     // WORKAROUND: AlertDialog's close response doesn't work
     dialog.connect_response(None, move |d, _| {
@@ -486,226 +2124,218 @@ pub fn get_game_path(parent_window: &ApplicationWindow, files: &[GioFile], model
     dialog.present(Some(parent_window));
 }
 
-// Save game path to config file
-fn save_game_path() {
-    // Create config file
-    let keyfile = KeyFile::new();
-    keyfile.set_string(
-        "settings",
-        "game-path",
-        &crate::GAME_PATH
-            .get()
-            .unwrap()
-            .clone()
-            .into_os_string()
-            .into_string()
-            .unwrap()
-    );
-
-    // Get config directory
-    let config_dir = glib::user_config_dir().join("EternalModManager");
-
-    // Create directory if necessary
-    if !config_dir.exists() {
-        if fs::create_dir_all(&config_dir).is_err() {
-            return;
-        }
-    }
-    else if config_dir.is_file() {
-        return;
-    }
-
-    if !config_dir.is_dir() {
-        return;
-    }
-
-    // Save config file
-    keyfile
-        .save_to_file(config_dir.join("config"))
-        .expect("Could not save config file");
-}
-
-#[cfg(target_os = "windows")]
-// Check for the modding tools on Windows
-fn check_modding_tools(parent_window: &ApplicationWindow) {
-    // Check if injector batch is present
-    if !crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjector.bat")
-        .is_file()
-    {
-        // Disable parent window
-        parent_window.set_sensitive(false);
-
-        // Create error dialog
-        let err_dialog = AlertDialog::builder()
-            .heading("Can't find EternalModInjector.bat.")
-            .body("Make sure that the modding tools are installed.")
-            .default_response("ok")
-            .close_response("ok")
-            .build();
-
-        err_dialog.add_responses(&[("ok", "_Ok")]);
-
-        // WORKAROUND: AlertDialog's close response doesn't work
-        err_dialog.connect_destroy(|dialog| {
-            dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
-        });
-
-        let signal = err_dialog.connect_response(
-            None,
-            clone!(
-                #[weak]
-                parent_window,
-                move |_, _| {
-                    // Exit
-                    parent_window.close();
-                }
-            )
-        );
+#[cfg(target_os = "linux")]
+// Look for a newer release of the modding tools, and offer to install it
+// Only asked checks report that the tools are up to date, or that the check failed
+fn check_modding_tools_update(window: &ApplicationWindow, asked: bool) {
+    let (tx, rx) = mpsc::channel();
 
-        // WORKAROUND: AlertDialog's close response doesn't work
-        err_dialog.connect_response(None, move |d, _| {
-            d.block_signal(&signal);
-        });
+    thread::spawn(move || {
+        let _ = tx.send(modding_tools::release::check_update(asked));
+    });
 
-        err_dialog.present(Some(parent_window));
-    }
+    // Poll the result, since waiting for it would freeze the window
+    glib::timeout_add_local(
+        Duration::from_millis(100),
+        clone!(
+            #[weak]
+            window,
+            #[upgrade_or]
+            ControlFlow::Break,
+            move || match rx.try_recv() {
+                Ok(result) => {
+                    show_modding_tools_update(&window, asked, result);
+                    ControlFlow::Break
+                },
+                Err(TryRecvError::Empty) => ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => ControlFlow::Break
+            }
+        )
+    );
 }
 
 #[cfg(target_os = "linux")]
-// Check for the modding tools on Linux (and download them)
-fn check_modding_tools(parent_window: &ApplicationWindow) {
-    use std::io::Cursor;
+// Offer to install a newer release of the modding tools
+fn show_modding_tools_update(
+    window: &ApplicationWindow, asked: bool, result: Result<Option<Release>, ReleaseError>
+) {
+    let release = match result {
+        Ok(Some(release)) => release,
+        Ok(None) => {
+            if asked {
+                show_message(window, "The modding tools are up to date.", "");
+            }
 
-    use adw::ResponseAppearance;
+            return;
+        },
+        Err(e) => {
+            if asked {
+                show_message(
+                    window,
+                    "Failed to check for modding tools updates.",
+                    &e.to_string()
+                );
+            }
 
-    // Check if injector batch is present
-    if !crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjectorShell.sh")
-        .is_file()
-    {
-        // Disable parent window
-        parent_window.set_sensitive(false);
-
-        // Create question dialog
-        let dialog = AlertDialog::builder()
-            .heading("Couldn't find the modding tools.")
-            .body("Do you want to download them?")
-            .default_response("yes")
-            .close_response("no")
-            .build();
-
-        dialog.add_responses(&[("yes", "_Yes"), ("no", "_No")]);
-        dialog.set_response_appearance("yes", ResponseAppearance::Suggested);
-
-        // WORKAROUND: AlertDialog's close response doesn't work
-        dialog.connect_destroy(|dialog| {
-            dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
-        });
+            return;
+        }
+    };
 
-        let signal = dialog.connect_response(None, clone!(#[weak] parent_window, move |_, result| {
-            // Check user selection
-            if result == "no" {
-                // Exit
-                parent_window.close();
-                return;
-            }
+    let installed = match modding_tools::installed_version() {
+        Some(v) => format!("version {}", v),
+        None => String::from("an unknown version")
+    };
 
-            // Download modding tools
-            let main_context = MainContext::default();
+    let unverified = if release.is_verified() {
+        ""
+    }
+    else {
+        "\n\nThis release doesn't publish a checksum, so the download can't be verified."
+    };
 
-            main_context.spawn_local(clone!(#[weak] parent_window, async move {
-                // Get request
-                let bytes = reqwest::blocking::get(
-                    "https://github.com/leveste/EternalBasher/releases/latest/download/EternalModInjectorShell.zip"
-                ).and_then(|r| r.bytes());
-
-                // Check for errors
-                if bytes.is_err() {
-                    // Create error dialog
-                    let err_dialog = AlertDialog::builder()
-                        .heading("Failed to download the modding tools.")
-                        .body("Make sure that you are connected to the internet.")
-                        .default_response("ok")
-                        .close_response("ok")
-                        .build();
-
-                    err_dialog.add_responses(&[("ok", "_Ok")]);
-
-                    // WORKAROUND: AlertDialog's close response doesn't work
-                    err_dialog.connect_destroy(|dialog| {
-                        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
-                    });
-
-                    let signal = err_dialog.connect_response(None, clone!(#[weak] parent_window, move |_, _| {
-                        // Exit
-                        parent_window.close();
-                    }));
+    // Create question dialog
+    let dialog = AlertDialog::builder()
+        .heading("Modding tools update available.")
+        .body(format!(
+            "Version {} of the modding tools is available, and {} is installed.\n\nThe current tools are \
+             backed up, so the update can be rolled back.{}",
+            release.version, installed, unverified
+        ))
+        .default_response("update")
+        .close_response("later")
+        .build();
 
-                    // WORKAROUND: AlertDialog's close response doesn't work
-                    err_dialog.connect_response(None, move |d, _| {
-                        d.block_signal(&signal);
-                    });
+    dialog.add_responses(&[("update", "_Update"), ("later", "_Later")]);
+    dialog.set_response_appearance("update", ResponseAppearance::Suggested);
 
-                    err_dialog.present(Some(&parent_window));
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
 
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            window,
+            move |_, result| {
+                if result != "update" {
                     return;
                 }
 
-                let mut content =  Cursor::new(bytes.unwrap());
+                // Disable window while updating
+                window.set_sensitive(false);
+
+                install_modding_tools(&window, Some(release.clone()), |window, result| {
+                    window.set_sensitive(true);
+
+                    match result {
+                        Ok(version) => show_message(
+                            window,
+                            "Modding tools updated.",
+                            &format!("Version {} of the modding tools was installed.", version)
+                        ),
+                        Err(ToolsError::Release(ReleaseError::Cancelled)) => {},
+                        Err(e) => show_message(window, "Failed to update the modding tools.", &e.to_string())
+                    }
+                });
+            }
+        )
+    );
 
-                // Unzip file
-                if ZipArchive::new(&mut content).and_then(|mut z| z.extract(crate::GAME_PATH.get().unwrap())).is_err() {
-                    // Create error dialog
-                    let err_dialog = AlertDialog::builder()
-                        .heading("Failed to download the modding tools.")
-                        .body("Make sure that you are connected to the internet.")
-                        .default_response("ok")
-                        .close_response("ok")
-                        .build();
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
 
-                    err_dialog.add_responses(&[("ok", "_Ok")]);
+    dialog.present(Some(window));
+}
 
-                    // WORKAROUND: AlertDialog's close response doesn't work
-                    err_dialog.connect_destroy(|dialog| {
-                        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
-                    });
+#[cfg(target_os = "linux")]
+// Restore the modding tools from before the latest update after confirmation
+fn roll_back_modding_tools(window: &ApplicationWindow) {
+    if !modding_tools::has_backup() {
+        show_message(
+            window,
+            "Can't restore the modding tools.",
+            &ToolsError::NoBackup.to_string()
+        );
+        return;
+    }
 
-                    let signal = err_dialog.connect_response(None, clone!(#[weak] parent_window, move |_, _| {
-                        // Exit
-                        parent_window.close();
-                    }));
+    let dialog = AlertDialog::builder()
+        .heading("Restore previous modding tools?")
+        .body("The modding tools from before the latest update will be restored.")
+        .default_response("no")
+        .close_response("no")
+        .build();
 
-                    // WORKAROUND: AlertDialog's close response doesn't work
-                    err_dialog.connect_response(None, move |d, _| {
-                        d.block_signal(&signal);
-                    });
+    dialog.add_responses(&[("yes", "_Restore"), ("no", "_Cancel")]);
 
-                    err_dialog.present(Some(&parent_window));
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_destroy(|dialog| {
+        dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+    });
 
+    let signal = dialog.connect_response(
+        None,
+        clone!(
+            #[weak]
+            window,
+            move |_, result| {
+                if result != "yes" {
                     return;
                 }
 
-                // Re-enable parent
-                parent_window.set_sensitive(true);
-            }));
-        }));
+                match modding_tools::roll_back() {
+                    Ok(()) => show_message(
+                        &window,
+                        "Modding tools restored.",
+                        &match modding_tools::installed_version() {
+                            Some(v) => format!("Version {} of the modding tools was restored.", v),
+                            None => String::from("The previous modding tools were restored.")
+                        }
+                    ),
+                    Err(e) => show_message(&window, "Failed to restore the modding tools.", &e.to_string())
+                }
+            }
+        )
+    );
 
-        // WORKAROUND: AlertDialog's close response doesn't work
-        dialog.connect_response(None, move |d, _| {
-            d.block_signal(&signal);
-        });
+    // WORKAROUND: AlertDialog's close response doesn't work
+    dialog.connect_response(None, move |d, _| {
+        d.block_signal(&signal);
+    });
 
-        dialog.present(Some(parent_window));
-    }
+    dialog.present(Some(window));
 }
 
-// Initialize the watcher on the game and mod directories
-fn init_watcher(model: &Model) {
+// Watcher on the current game folder, replaced when switching game folders
+static WATCHER: Mutex<Option<Debouncer<RecommendedWatcher>>> = Mutex::new(None);
+
+// Initialize the watcher on the game and mod directories, stopping the previous one
+fn init_watcher(window: &ApplicationWindow, model: &Model) {
+    // Check for game updates when the fingerprinted files change
+    let (update_tx, update_rx) = mpsc::channel();
+
+    glib::timeout_add_local(
+        Duration::from_millis(500),
+        clone!(
+            #[weak]
+            window,
+            #[upgrade_or]
+            ControlFlow::Break,
+            move || match update_rx.try_recv() {
+                Ok(()) => {
+                    ActionGroupExt::activate_action(&window, "check-game-update", None);
+                    ControlFlow::Continue
+                },
+                Err(TryRecvError::Empty) => ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => ControlFlow::Break
+            }
+        )
+    );
+
     thread::spawn(clone!(
         #[weak]
         model,
@@ -713,8 +2343,8 @@ fn init_watcher(model: &Model) {
             let (tx, rx) = mpsc::channel();
 
             // Create the mod directories
-            let mods_dir = crate::GAME_PATH.get().unwrap().join("Mods");
-            let disabled_mods_dir = crate::GAME_PATH.get().unwrap().join("DisabledMods");
+            let mods_dir = crate::game_path().join("Mods");
+            let disabled_mods_dir = crate::game_path().join("DisabledMods");
 
             if !mods_dir.exists() {
                 fs::create_dir(&mods_dir).unwrap();
@@ -736,13 +2366,29 @@ fn init_watcher(model: &Model) {
                 .watcher()
                 .watch(&disabled_mods_dir, RecursiveMode::NonRecursive)
                 .unwrap();
-            let _ = debouncer.watcher().watch(
-                &crate::GAME_PATH
-                    .get()
-                    .unwrap()
-                    .join("EternalModInjector Settings.txt"),
-                RecursiveMode::NonRecursive
-            );
+
+            // Watch the folders of the settings file and the fingerprinted files,
+            // since watches on files are lost when they're replaced
+            let settings_path = injector_settings::settings_path();
+            let fingerprint_files = game_update::fingerprint_files();
+
+            let mut folders = fingerprint_files
+                .iter()
+                .chain([&settings_path])
+                .filter_map(|f| f.parent())
+                .collect::<Vec<&Path>>();
+            folders.sort();
+            folders.dedup();
+
+            for folder in folders {
+                let _ = debouncer.watcher().watch(folder, RecursiveMode::NonRecursive);
+            }
+
+            // Replace the previous watcher, ending its thread
+            *WATCHER.lock().unwrap() = Some(debouncer);
+
+            // Check if the game was updated since the last run
+            let _ = update_tx.send(());
 
             // Get mods
             let main_context = MainContext::default();
@@ -751,18 +2397,34 @@ fn init_watcher(model: &Model) {
                 #[weak]
                 model,
                 async move {
-                    get_mods(&model);
+                    model.replace(&mods::scan());
                 }
             ));
 
             // Listen to watcher
             for res in rx {
-                if res.is_ok() {
+                let events = match res {
+                    Ok(events) => events,
+                    Err(_) => continue
+                };
+
+                if events.iter().any(|e| fingerprint_files.contains(&e.path)) {
+                    let _ = update_tx.send(());
+                }
+
+                // Skip the other files in the game folders
+                let mods_changed = events.iter().any(|e| {
+                    e.path == settings_path
+                        || e.path.parent() == Some(&mods_dir)
+                        || e.path.parent() == Some(&disabled_mods_dir)
+                });
+
+                if mods_changed {
                     main_context.spawn(clone!(
                         #[weak]
                         model,
                         async move {
-                            get_mods(&model);
+                            model.replace(&mods::scan());
                         }
                     ));
                 }
@@ -770,303 +2432,3 @@ fn init_watcher(model: &Model) {
         }
     ));
 }
-
-// Check if mod is safe for online play
-fn is_mod_online_safe(mod_zip: &mut ZipArchive<File>) -> bool {
-    let mut assets_info_jsons = Vec::new();
-    static UNSAFE_RESOURCE_KEYWORDS: &[&str] = &["gameresources", "pvp", "shell", "warehouse"];
-
-    // Iterate through zip's entries
-    for mod_file in mod_zip.file_names() {
-        let mod_file_entry = mod_file.to_lowercase();
-
-        // Skip directories
-        if mod_file_entry.ends_with('/') {
-            continue;
-        }
-
-        // Skip top-level files
-        if !mod_file_entry.contains('/') {
-            continue;
-        }
-
-        // Allow hidden system files
-        if mod_file_entry.ends_with("desktop.ini") || mod_file_entry.ends_with(".ds_store") {
-            continue;
-        }
-
-        let container_name = mod_file_entry.split('/').next().unwrap();
-        let mod_name = &mod_file_entry[container_name.len() + 1..];
-        let sound_container_path = crate::GAME_PATH
-            .get()
-            .unwrap()
-            .join("base")
-            .join("sound")
-            .join("soundbanks")
-            .join("pc")
-            .join(format!("{}.snd", container_name));
-
-        // Allow sound files
-        if sound_container_path.is_file() {
-            continue;
-        }
-
-        // Allow streamdb mods
-        if container_name == "streamdb" {
-            continue;
-        }
-
-        // Save AssetsInfo JSON files to be handled later
-        if mod_file_entry.starts_with("eternalmod/assetsinfo") && mod_file_entry.ends_with(".json") {
-            assets_info_jsons.push(mod_file.to_owned());
-        }
-
-        // Check if mod is modifying an online-unsafe resource
-        let is_modifying_unsafe_resource = UNSAFE_RESOURCE_KEYWORDS
-            .iter()
-            .any(|&k| container_name.starts_with(k));
-
-        // Files with .lwo extension are unsafe
-        if PathBuf::from(&mod_file_entry)
-            .extension()
-            .unwrap_or_default()
-            .to_str()
-            .unwrap()
-            .contains(".lwo")
-            && is_modifying_unsafe_resource
-        {
-            return false;
-        }
-
-        // Allow modification of everything outside of generated/decls, except .entities files
-        if !mod_name.starts_with("generated/decls") && !mod_name.ends_with(".entities") {
-            continue;
-        }
-
-        // Do not allow mods to modify non-whitelisted files in unsafe resources
-        static ONLINE_SAFE_KEYWORDS: &[&str] = &[
-            "/eternalmod/",
-            ".tga",
-            ".png",
-            ".swf",
-            ".bimage",
-            "/advancedscreenviewshake/",
-            "/audiolog/",
-            "/audiologstory/",
-            "/automap/",
-            "/automapplayerprofile/",
-            "/automapproperties/",
-            "/automapsoundprofile/",
-            "/env/",
-            "/font/",
-            "/fontfx/",
-            "/fx/",
-            "/gameitem/",
-            "/globalfonttable/",
-            "/gorebehavior/",
-            "/gorecontainer/",
-            "/gorewounds/",
-            "/handsbobcycle/",
-            "/highlightlos/",
-            "/highlights/",
-            "/hitconfirmationsoundsinfo/",
-            "/hud/",
-            "/hudelement/",
-            "/lightrig/",
-            "/lodgroup/",
-            "/material2/",
-            "/md6def/",
-            "/modelasset/",
-            "/particle/",
-            "/particlestage/",
-            "/renderlayerdefinition/",
-            "/renderparm/",
-            "/renderparmmeta/",
-            "/renderprogflag/",
-            "/ribbon2/",
-            "/rumble/",
-            "/soundevent/",
-            "/soundpack/",
-            "/soundrtpc/",
-            "/soundstate/",
-            "/soundswitch/",
-            "/speaker/",
-            "/staticimage/",
-            "/swfresources/",
-            "/uianchor/",
-            "/uicolor/",
-            "/weaponreticle/",
-            "/weaponreticleswfinfo/",
-            "/entitydef/light/",
-            "/entitydef/fx",
-            "/impacteffect/",
-            "/uiweapon/",
-            "/globalinitialwarehouse/",
-            "/globalshell/",
-            "/warehouseitem/",
-            "/warehouseofflinecontainer/",
-            "/tooltip/",
-            "/livetile/",
-            "/tutorialevent/",
-            "maps/game/dlc/",
-            "maps/game/dlc2/",
-            "maps/game/horde/",
-            "maps/game/hub/",
-            "maps/game/shell/",
-            "maps/game/sp/",
-            "maps/game/tutorials/",
-            "/decls/campaign/"
-        ];
-
-        if !ONLINE_SAFE_KEYWORDS.iter().any(|&k| mod_name.contains(k)) && is_modifying_unsafe_resource {
-            return false;
-        }
-    }
-
-    // Don't allow injecting files into the online-unsafe resources
-    for assets_info_entry in assets_info_jsons {
-        let resource_name = assets_info_entry.split('/').next().unwrap();
-
-        // Unzip and deserialize JSON
-        if let Ok(assets_info_file) = mod_zip.by_name(&assets_info_entry) {
-            let deserialize: Result<Value> = serde_json::from_reader(assets_info_file);
-
-            if let Ok(assets_info) = deserialize {
-                if assets_info["resources"].is_array()
-                    && !assets_info["resources"].as_array().unwrap().is_empty()
-                    && UNSAFE_RESOURCE_KEYWORDS
-                        .iter()
-                        .any(|&k| resource_name.starts_with(k))
-                {
-                    return false;
-                }
-            }
-        }
-    }
-
-    true
-}
-
-// Load a mod into the list
-fn load_mod_into_list(
-    mod_path: PathBuf, enabled: bool, only_load_online_safe: bool, mod_list: &mut Vector<ModData>
-) {
-    let file_name = mod_path.file_name().unwrap().to_str().unwrap();
-    let mod_data: ModData;
-
-    // Get mod data
-    if let Ok(file) = File::open(&mod_path) {
-        if let Ok(mut zip_file) = ZipArchive::new(file) {
-            // Check if mod is online safe
-            let is_online_safe = is_mod_online_safe(&mut zip_file);
-
-            // Read properties from EternalMod.json
-            if let Ok(eternalmod_file) = zip_file.by_name("EternalMod.json") {
-                let deserialize: Result<Value> = serde_json::from_reader(eternalmod_file);
-
-                if let Ok(eternalmod) = deserialize {
-                    mod_data = ModData::new(
-                        file_name,
-                        true,
-                        enabled,
-                        is_online_safe,
-                        only_load_online_safe,
-                        eternalmod
-                    );
-                }
-                else {
-                    mod_data = ModData::new(
-                        file_name,
-                        true,
-                        enabled,
-                        is_online_safe,
-                        only_load_online_safe,
-                        Value::Null
-                    );
-                }
-            }
-            else {
-                mod_data = ModData::new(
-                    file_name,
-                    true,
-                    enabled,
-                    is_online_safe,
-                    only_load_online_safe,
-                    Value::Null
-                );
-            }
-        }
-        else {
-            mod_data = ModData::new(
-                file_name,
-                false,
-                enabled,
-                false,
-                only_load_online_safe,
-                Value::Null
-            );
-        }
-    }
-    else {
-        mod_data = ModData::new(
-            file_name,
-            false,
-            enabled,
-            false,
-            only_load_online_safe,
-            Value::Null
-        );
-    }
-
-    // Add to list
-    mod_list.push_back(mod_data);
-}
-
-// Get all mods and add them to the mod list
-fn get_mods(mods_list: &Model) {
-    // Buffer to store newly loaded mods
-    let mut buffer_mod_list = Vector::new();
-
-    // Check if only online safe mods should be loaded
-    let settings_path = crate::GAME_PATH
-        .get()
-        .unwrap()
-        .join("EternalModInjector Settings.txt");
-
-    let only_load_online_safe = match fs::read_to_string(settings_path) {
-        Ok(settings) => settings.contains(":ONLINE_SAFE=1"),
-        Err(_) => false
-    };
-
-    // Get enabled mods
-    for mod_file in fs::read_dir(crate::GAME_PATH.get().unwrap().join("Mods"))
-        .unwrap()
-        .filter_map(|f| f.ok())
-    {
-        load_mod_into_list(mod_file.path(), true, only_load_online_safe, &mut buffer_mod_list);
-    }
-
-    // Get disabled mods
-    for mod_file in fs::read_dir(crate::GAME_PATH.get().unwrap().join("DisabledMods"))
-        .unwrap()
-        .filter_map(|f| f.ok())
-    {
-        load_mod_into_list(
-            mod_file.path(),
-            false,
-            only_load_online_safe,
-            &mut buffer_mod_list
-        );
-    }
-
-    // Sort buffer mod list
-    buffer_mod_list.sort_by(|a, b| {
-        a.filename()
-            .unwrap()
-            .to_lowercase()
-            .cmp(&b.filename().unwrap().to_lowercase())
-    });
-
-    // Replace mod list
-    mods_list.replace(&buffer_mod_list);
-}
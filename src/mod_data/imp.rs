@@ -1,8 +1,13 @@
-use std::sync::{atomic::AtomicBool, RwLock};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32},
+    RwLock
+};
 
 use adw::prelude::*;
 use gtk::glib::{self, subclass::prelude::*, ParamSpec, Properties, Value};
 
+use crate::eternal_mod::EternalMod;
+
 #[derive(Default, Properties)]
 #[properties(wrapper_type = super::ModData)]
 pub struct ModData {
@@ -13,12 +18,18 @@ pub struct ModData {
     #[property(get, set)]
     is_valid: AtomicBool,
     #[property(get, set)]
+    invalid_reason: RwLock<Option<String>>,
+    #[property(get, set)]
     is_enabled: AtomicBool,
     #[property(get, set)]
     is_online_safe: AtomicBool,
     #[property(get, set)]
+    is_compatible: AtomicBool,
+    #[property(get, set)]
     online_safety_message: RwLock<Option<String>>,
     #[property(get, set)]
+    online_safety_details: RwLock<Option<String>>,
+    #[property(get, set)]
     icon: RwLock<Option<String>>,
     #[property(get, set)]
     author: RwLock<Option<String>>,
@@ -31,7 +42,13 @@ pub struct ModData {
     #[property(get, set)]
     required_version: RwLock<Option<String>>,
     #[property(get, set)]
-    tooltip: RwLock<Option<String>>
+    warnings: RwLock<Option<String>>,
+    #[property(get, set)]
+    conflicts: AtomicU32,
+    #[property(get, set)]
+    tooltip: RwLock<Option<String>>,
+    pub(super) eternal_mod: RwLock<EternalMod>,
+    pub(super) entries: RwLock<Vec<String>>
 }
 
 #[glib::object_subclass]
@@ -1,20 +1,29 @@
 mod imp;
 
-use gtk::glib::{wrapper, Object};
-use serde_json::Value;
+use gtk::glib::{self, subclass::prelude::*, wrapper, Object};
+
+use crate::{eternal_mod::EternalMod, online_safety::OnlineSafetyVerdict};
 
 wrapper! {
     pub struct ModData(ObjectSubclass<imp::ModData>);
 }
 
 impl ModData {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        filename: &str, is_valid: bool, is_enabled: bool, is_online_safe: bool, only_load_online_safe: bool,
-        json: Value
+        filename: &str, invalid_reason: Option<&str>, is_enabled: bool,
+        online_safety: Option<&OnlineSafetyVerdict>, only_load_online_safe: bool, eternal_mod: &EternalMod,
+        warnings: &[String], loader_version: Option<u32>
     ) -> ModData {
+        // Get online safety verdict
+        let is_online_safe = online_safety.is_some_and(|v| v.is_safe());
+        let online_safety_details = online_safety.map(|v| v.details()).unwrap_or_default();
+
         // Get online safety message and icon
-        let (color, tooltip, icon) = if !is_valid {
-            ("red", "Invalid .zip file", "✗")
+        let invalid_message;
+        let (color, tooltip, icon) = if let Some(reason) = invalid_reason {
+            invalid_message = format!("Invalid .zip file: {}", reason);
+            ("red", invalid_message.as_str(), "✗")
         }
         else if is_online_safe {
             ("greenyellow", "This mod is safe for use in public matches.", "✓")
@@ -38,38 +47,88 @@ impl ModData {
         };
 
         // Apply colors
-        let online_safety_message = format!("<span foreground='{}'>{}</span>", color, tooltip);
+        let online_safety_message = format!(
+            "<span foreground='{}'>{}</span>",
+            color,
+            glib::markup_escape_text(tooltip)
+        );
         let colored_icon = format!("<span foreground='{}' weight='bold'>{}</span>", color, icon);
 
-        // Get properties from json
-        let name = json["name"].as_str().unwrap_or(filename);
-        let author = json["author"].as_str().unwrap_or("Unknown.");
-        let description = json["description"].as_str().unwrap_or("Not specified.");
-        let version = json["version"].as_str().unwrap_or("Not specified.");
-        let load_priority = json["loadPriority"]
-            .as_i64()
+        // Get properties from EternalMod.json
+        let name = eternal_mod.name.as_deref().unwrap_or(filename);
+        let author = eternal_mod.author.as_deref().unwrap_or("Unknown.");
+        let description = eternal_mod.description.as_deref().unwrap_or("Not specified.");
+        let version = eternal_mod.version.as_deref().unwrap_or("Not specified.");
+        let load_priority = eternal_mod
+            .load_priority
             .map(|i| i.to_string())
             .unwrap_or_else(|| "Not specified.".into());
-        let required_version = json["loadPriority"]
-            .as_i64()
+        let required_version = eternal_mod
+            .required_version
             .map(|i| i.to_string())
             .unwrap_or_else(|| "Not specified.".into());
 
-        // Create and return object
-        Object::builder()
+        // Check if the installed mod loader is new enough
+        let mut warnings = warnings.to_vec();
+        let is_compatible = match (eternal_mod.required_version, loader_version) {
+            (Some(required), Some(installed)) if required > installed as i64 => {
+                warnings.push(format!(
+                    "This mod requires mod loader version {} or newer, but version {} is installed.",
+                    required, installed
+                ));
+                false
+            },
+            _ => true
+        };
+
+        // Add warnings to tooltip
+        let warnings = warnings.join("\n");
+        let tooltip = if warnings.is_empty() {
+            tooltip.to_owned()
+        }
+        else {
+            format!("{}\n\n{}", tooltip, warnings)
+        };
+
+        // Create object
+        let mod_data: ModData = Object::builder()
             .property("name", name)
             .property("filename", filename)
-            .property("is-valid", is_valid)
+            .property("is-valid", invalid_reason.is_none())
+            .property("invalid-reason", invalid_reason)
             .property("is-enabled", is_enabled)
             .property("is-online-safe", is_online_safe)
+            .property("is-compatible", is_compatible)
             .property("online-safety-message", online_safety_message)
+            .property("online-safety-details", online_safety_details)
             .property("icon", colored_icon)
             .property("author", author)
             .property("description", description)
             .property("version", version)
             .property("load-priority", load_priority)
             .property("required-version", required_version)
+            .property("warnings", warnings)
             .property("tooltip", tooltip)
-            .build()
+            .build();
+
+        // Keep parsed EternalMod.json
+        *mod_data.imp().eternal_mod.write().unwrap() = eternal_mod.clone();
+
+        mod_data
+    }
+
+    // Get the parsed EternalMod.json
+    pub fn eternal_mod(&self) -> EternalMod {
+        self.imp().eternal_mod.read().unwrap().clone()
+    }
+
+    // Get the game files modified by the mod
+    pub fn entries(&self) -> Vec<String> {
+        self.imp().entries.read().unwrap().clone()
+    }
+
+    // Set the game files modified by the mod
+    pub fn set_entries(&self, entries: Vec<String>) {
+        *self.imp().entries.write().unwrap() = entries;
     }
 }
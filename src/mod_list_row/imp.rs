@@ -9,7 +9,7 @@ use gtk::{
     Window
 };
 
-use crate::mod_data::ModData;
+use crate::{mod_data::ModData, mods};
 
 #[derive(Default, Properties, Debug)]
 #[properties(wrapper_type = super::ListBoxRow)]
@@ -75,7 +75,7 @@ impl ObjectImpl for ListBoxRow {
                             continue;
                         }
 
-                        let mods_folder = crate::GAME_PATH.get().unwrap().join("Mods");
+                        let mods_folder = crate::game_path().join("Mods");
                         let new_path = mods_folder.join(path.file_name().unwrap());
 
                         // Check if it's already in the target folder
@@ -101,8 +101,8 @@ impl ObjectImpl for ListBoxRow {
             move |_, _| {
                 // Get mod folder
                 let parent_folder = match item.is_enabled() {
-                    true => crate::GAME_PATH.get().unwrap().join("Mods"),
-                    false => crate::GAME_PATH.get().unwrap().join("DisabledMods")
+                    true => crate::game_path().join("Mods"),
+                    false => crate::game_path().join("DisabledMods")
                 };
 
                 // Open folder
@@ -131,8 +131,8 @@ impl ObjectImpl for ListBoxRow {
             move |_, _| {
                 // Get mod folder
                 let parent_folder = match item.is_enabled() {
-                    true => crate::GAME_PATH.get().unwrap().join("Mods"),
-                    false => crate::GAME_PATH.get().unwrap().join("DisabledMods")
+                    true => crate::game_path().join("Mods"),
+                    false => crate::game_path().join("DisabledMods")
                 };
 
                 // Get mod path
@@ -192,7 +192,9 @@ impl ObjectImpl for ListBoxRow {
         // Get grid elements
         let check = grid.child_at(0, 0).unwrap().downcast::<CheckButton>().unwrap();
         let name = grid.child_at(1, 0).unwrap().downcast::<Label>().unwrap();
-        let icon = grid.child_at(2, 0).unwrap().downcast::<Label>().unwrap();
+        let icon = grid.child_at(4, 0).unwrap().downcast::<Label>().unwrap();
+        let warning_icon = row_builder.object::<Label>("WarningIcon").unwrap();
+        let conflict_icon = row_builder.object::<Label>("ConflictIcon").unwrap();
 
         // Bind properties
         item.bind_property("is-enabled", &check, "active")
@@ -206,30 +208,27 @@ impl ObjectImpl for ListBoxRow {
         item.bind_property("tooltip", &grid, "tooltip-text")
             .sync_create()
             .build();
+        item.bind_property("warnings", &warning_icon, "visible")
+            .transform_to(|_, warnings: Option<String>| Some(warnings.is_some_and(|w| !w.is_empty())))
+            .sync_create()
+            .build();
+        item.bind_property("conflicts", &conflict_icon, "visible")
+            .transform_to(|_, conflicts: u32| Some(conflicts > 0))
+            .sync_create()
+            .build();
+        item.bind_property("conflicts", &conflict_icon, "tooltip-text")
+            .transform_to(|_, conflicts: u32| {
+                Some(format!(
+                    "This mod modifies {} file(s) that other enabled mods also modify.",
+                    conflicts
+                ))
+            })
+            .sync_create()
+            .build();
 
         // Move mod on checkbox click
         check.connect_toggled(move |_| {
-            // Get mod paths
-            let enabled_mod_path = crate::GAME_PATH
-                .get()
-                .unwrap()
-                .join("Mods")
-                .join(item.filename().unwrap());
-            let disabled_mod_path = crate::GAME_PATH
-                .get()
-                .unwrap()
-                .join("DisabledMods")
-                .join(item.filename().unwrap());
-
-            // Check if mod is enabled
-            if item.is_enabled() {
-                // Move to enabled folder
-                let _ = fs::rename(disabled_mod_path, enabled_mod_path);
-            }
-            else {
-                // Move to disabled folder
-                let _ = fs::rename(enabled_mod_path, disabled_mod_path);
-            }
+            let _ = mods::set_enabled(&item.filename().unwrap(), item.is_enabled());
         });
 
         // Set child
@@ -0,0 +1,240 @@
+#[cfg(target_os = "linux")]
+pub mod release;
+
+use std::{
+    fmt, fs,
+    io::{self, Cursor},
+    path::{Path, PathBuf}
+};
+
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+#[cfg(target_os = "linux")]
+use self::release::ReleaseError;
+use crate::{
+    archive::{self, ArchiveError},
+    config
+};
+
+// Modding tools archive, and the files it must have for the tools to work
+#[cfg(target_os = "linux")]
+pub const ARCHIVE_NAME: &str = "EternalModInjectorShell.zip";
+#[cfg(target_os = "linux")]
+const EXPECTED_FILES: [&str; 2] = ["EternalModInjectorShell.sh", "base/DEternal_loadMods"];
+
+#[cfg(target_os = "windows")]
+pub const ARCHIVE_NAME: &str = "EternalModInjector.zip";
+#[cfg(target_os = "windows")]
+const EXPECTED_FILES: [&str; 2] = ["EternalModInjector.bat", "base/DEternal_loadMods.exe"];
+
+// Injector script of the modding tools for the other platform, to explain why an archive doesn't work
+#[cfg(target_os = "linux")]
+const OTHER_PLATFORM_SCRIPT: (&str, &str) = ("EternalModInjector.bat", "Windows");
+#[cfg(target_os = "windows")]
+const OTHER_PLATFORM_SCRIPT: (&str, &str) = ("EternalModInjectorShell.sh", "Linux");
+
+// Error found while installing or rolling back the modding tools
+#[derive(Debug)]
+pub enum ToolsError {
+    #[cfg(target_os = "linux")]
+    Release(ReleaseError),
+    InvalidArchive(ArchiveError),
+    Extract(ArchiveError),
+    MissingFile(&'static str),
+    WrongPlatform(&'static str),
+    Io(io::Error),
+    #[cfg(target_os = "linux")]
+    NoBackup
+}
+
+impl fmt::Display for ToolsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(target_os = "linux")]
+            ToolsError::Release(e) => write!(f, "{}", e),
+            ToolsError::InvalidArchive(e) => write!(f, "The archive can't be used: {}", e),
+            ToolsError::Extract(e) => write!(f, "Failed to extract the tools into the game directory: {}", e),
+            ToolsError::MissingFile(name) => write!(
+                f,
+                "The archive doesn't have {}. Make sure that it's {}.",
+                name, ARCHIVE_NAME
+            ),
+            ToolsError::WrongPlatform(platform) => write!(
+                f,
+                "The archive has the modding tools for {}. Use {} instead.",
+                platform, ARCHIVE_NAME
+            ),
+            ToolsError::Io(e) => write!(f, "Failed to copy the tools: {}", e),
+            #[cfg(target_os = "linux")]
+            ToolsError::NoBackup => write!(f, "There are no previous modding tools to restore.")
+        }
+    }
+}
+
+// Previous tools kept to roll back an update
+#[derive(Serialize, Deserialize)]
+struct Backup {
+    // Version of the previous tools, if it was known
+    version: Option<String>,
+    // Files the update added, which are removed on roll back
+    added: Vec<String>
+}
+
+// Check if the modding tools are installed in the current game folder
+pub fn is_installed() -> bool {
+    crate::game_path().join(EXPECTED_FILES[0]).is_file()
+}
+
+// Get the version of the modding tools installed in the current game folder, if it's known
+pub fn installed_version() -> Option<String> {
+    config::load_user()
+        .string("tools-versions", &config::install_id(&crate::game_path()))
+        .ok()
+        .map(|v| v.to_string())
+}
+
+// Store the version of the modding tools installed in the current game folder
+fn set_installed_version(version: Option<&str>) -> bool {
+    // Load config file, keeping other settings
+    let keyfile = config::load_user();
+    let install_id = config::install_id(&crate::game_path());
+
+    match version {
+        Some(v) => keyfile.set_string("tools-versions", &install_id, v),
+        None => {
+            let _ = keyfile.remove_key("tools-versions", &install_id);
+        }
+    }
+
+    config::save(&keyfile)
+}
+
+// Get the folder keeping the previous tools of the current game folder
+fn backup_dir() -> PathBuf {
+    config::config_dir()
+        .join("tools-backup")
+        .join(config::install_id(&crate::game_path()))
+}
+
+// Check if there are previous tools to roll back to
+#[cfg(target_os = "linux")]
+pub fn has_backup() -> bool {
+    backup_dir().join("backup.json").is_file()
+}
+
+// Copy the tools files the archive will overwrite, and list the ones it will add
+fn back_up(archive: &mut ZipArchive<Cursor<Vec<u8>>>) -> Result<(), ToolsError> {
+    let game_path = crate::game_path();
+    let backup_dir = backup_dir();
+
+    // Only keep the tools from before the latest update
+    if backup_dir.exists() {
+        fs::remove_dir_all(&backup_dir).map_err(ToolsError::Io)?;
+    }
+
+    let files_dir = backup_dir.join("files");
+    fs::create_dir_all(&files_dir).map_err(ToolsError::Io)?;
+
+    let mut added = Vec::new();
+
+    for name in archive.file_names().filter(|n| !n.ends_with('/')) {
+        let path = game_path.join(name);
+
+        if path.is_file() {
+            let backup_path = files_dir.join(name);
+            fs::create_dir_all(backup_path.parent().unwrap()).map_err(ToolsError::Io)?;
+            fs::copy(&path, &backup_path).map_err(ToolsError::Io)?;
+        }
+        else {
+            added.push(name.to_owned());
+        }
+    }
+
+    let backup = Backup {
+        version: installed_version(),
+        added
+    };
+
+    fs::write(
+        backup_dir.join("backup.json"),
+        serde_json::to_string_pretty(&backup).unwrap()
+    )
+    .map_err(ToolsError::Io)
+}
+
+// Open a modding tools archive, checking that it has the tools for this platform
+fn open_archive(bytes: Vec<u8>) -> Result<ZipArchive<Cursor<Vec<u8>>>, ToolsError> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| ToolsError::InvalidArchive(ArchiveError::Zip(e)))?;
+
+    // The backup uses the entry names as paths too
+    archive::inspect(&mut archive).map_err(ToolsError::InvalidArchive)?;
+
+    let has_file = |name: &str| archive.file_names().any(|n| n == name);
+
+    if !has_file(EXPECTED_FILES[0]) && has_file(OTHER_PLATFORM_SCRIPT.0) {
+        return Err(ToolsError::WrongPlatform(OTHER_PLATFORM_SCRIPT.1));
+    }
+
+    if let Some(missing) = EXPECTED_FILES.iter().find(|f| !has_file(f)) {
+        return Err(ToolsError::MissingFile(missing));
+    }
+
+    Ok(archive)
+}
+
+// Extract a modding tools archive into the game folder, backing up the installed tools first
+// The version is unknown for tools that didn't come from a release
+pub fn install(bytes: Vec<u8>, version: Option<&str>) -> Result<(), ToolsError> {
+    let mut archive = open_archive(bytes)?;
+
+    if is_installed() {
+        back_up(&mut archive)?;
+    }
+
+    archive::extract(&mut archive, &crate::game_path()).map_err(ToolsError::Extract)?;
+
+    set_installed_version(version);
+    Ok(())
+}
+
+// Install the modding tools from an archive the user has
+pub fn install_archive(path: &Path) -> Result<(), ToolsError> {
+    let bytes = fs::read(path).map_err(ToolsError::Io)?;
+    install(bytes, None)
+}
+
+// Restore the tools from before the latest update
+#[cfg(target_os = "linux")]
+pub fn roll_back() -> Result<(), ToolsError> {
+    let backup_dir = backup_dir();
+
+    let backup: Backup = fs::read_to_string(backup_dir.join("backup.json"))
+        .ok()
+        .and_then(|b| serde_json::from_str(&b).ok())
+        .ok_or(ToolsError::NoBackup)?;
+
+    let game_path = crate::game_path();
+    let files_dir = backup_dir.join("files");
+
+    // Copy the previous files back
+    for entry in WalkDir::new(&files_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry.path().strip_prefix(&files_dir).unwrap();
+        fs::copy(entry.path(), game_path.join(relative)).map_err(ToolsError::Io)?;
+    }
+
+    // Remove the files the update added
+    for name in &backup.added {
+        let _ = fs::remove_file(game_path.join(name));
+    }
+
+    set_installed_version(backup.version.as_deref());
+    fs::remove_dir_all(&backup_dir).map_err(ToolsError::Io)
+}
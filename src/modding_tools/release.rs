@@ -0,0 +1,375 @@
+use std::{
+    env, fmt,
+    fs::{self, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration
+};
+
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::RANGE,
+    StatusCode
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{config, modding_tools};
+
+// Latest release of the modding tools
+const DEFAULT_RELEASE_URL: &str = "https://api.github.com/repos/leveste/EternalBasher/releases/latest";
+
+// Environment variable overriding the release endpoint, to test against a local server
+const RELEASE_URL_VAR: &str = "ETERNALMODMANAGER_TOOLS_URL";
+
+// Time to wait for the release server to connect, and for each read or write after that
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+// Release of the modding tools
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub version: String,
+    download_url: String,
+    sha256: Option<String>
+}
+
+// Error found while getting a release of the modding tools
+#[derive(Debug)]
+pub enum ReleaseError {
+    Network(reqwest::Error),
+    HttpStatus(StatusCode),
+    Interrupted(io::Error),
+    Json(serde_json::Error),
+    InvalidRelease(&'static str),
+    InvalidChecksum,
+    ChecksumMismatch,
+    Io(io::Error),
+    Cancelled
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReleaseError::Network(e) if e.is_timeout() => {
+                write!(f, "The connection to the release server timed out.")
+            },
+            ReleaseError::Network(e) if e.is_connect() => write!(
+                f,
+                "Couldn't connect to the release server. Make sure that you are connected to the internet."
+            ),
+            ReleaseError::Network(e) => write!(f, "Failed to reach the release server: {}", e),
+            ReleaseError::HttpStatus(status)
+                if *status == StatusCode::FORBIDDEN || *status == StatusCode::TOO_MANY_REQUESTS =>
+            {
+                write!(
+                    f,
+                    "The release server answered with HTTP {}. GitHub's rate limit might have been reached, \
+                     try again later.",
+                    status
+                )
+            },
+            ReleaseError::HttpStatus(status) => {
+                write!(f, "The release server answered with HTTP {}.", status)
+            },
+            ReleaseError::Interrupted(e) => write!(
+                f,
+                "The download was interrupted: {}\n\nTry again to resume it.",
+                e
+            ),
+            ReleaseError::Json(e) => write!(f, "Failed to parse the release information: {}", e),
+            ReleaseError::InvalidRelease(k) => write!(f, "The release information is missing \"{}\".", k),
+            ReleaseError::InvalidChecksum => {
+                write!(f, "The release publishes an invalid checksum for the tools.")
+            },
+            ReleaseError::ChecksumMismatch => {
+                write!(f, "The downloaded tools don't match the published checksum.")
+            },
+            ReleaseError::Io(e) => write!(f, "Failed to save the download: {}", e),
+            ReleaseError::Cancelled => write!(f, "The download was cancelled.")
+        }
+    }
+}
+
+// Get the release endpoint, which can be overridden by the environment or the config file
+// The endpoint must answer like GitHub's release API, with "tag_name" and "assets"
+pub fn release_url() -> String {
+    if let Some(url) = env::var(RELEASE_URL_VAR).ok().filter(|u| !u.is_empty()) {
+        return url;
+    }
+
+    config::load()
+        .string("settings", "tools-release-url")
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| DEFAULT_RELEASE_URL.to_owned())
+}
+
+// Create an HTTP client
+fn client() -> Result<Client, ReleaseError> {
+    // GitHub's API rejects requests without a user agent
+    Client::builder()
+        .user_agent(concat!("EternalModManager/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(ReleaseError::Network)
+}
+
+// Send a request, failing on error statuses
+fn send(request: RequestBuilder) -> Result<Response, ReleaseError> {
+    let response = request.send().map_err(ReleaseError::Network)?;
+    let status = response.status();
+
+    if !status.is_success() {
+        return Err(ReleaseError::HttpStatus(status));
+    }
+
+    Ok(response)
+}
+
+// Send a GET request, failing on error statuses
+fn get(url: &str) -> Result<Response, ReleaseError> {
+    send(client()?.get(url))
+}
+
+// Get the latest release of the modding tools and its checksum
+pub fn latest() -> Result<Release, ReleaseError> {
+    let text = get(&release_url())?.text().map_err(ReleaseError::Network)?;
+    let release: Value = serde_json::from_str(&text).map_err(ReleaseError::Json)?;
+
+    let version = release
+        .get("tag_name")
+        .and_then(Value::as_str)
+        .ok_or(ReleaseError::InvalidRelease("tag_name"))?
+        .to_owned();
+
+    let assets = release
+        .get("assets")
+        .and_then(Value::as_array)
+        .ok_or(ReleaseError::InvalidRelease("assets"))?;
+
+    let find_asset = |name: &str| {
+        assets
+            .iter()
+            .find(|a| a.get("name").and_then(Value::as_str) == Some(name))
+    };
+
+    let archive = find_asset(modding_tools::ARCHIVE_NAME)
+        .ok_or(ReleaseError::InvalidRelease(modding_tools::ARCHIVE_NAME))?;
+
+    let download_url = archive
+        .get("browser_download_url")
+        .and_then(Value::as_str)
+        .ok_or(ReleaseError::InvalidRelease("browser_download_url"))?
+        .to_owned();
+
+    // Use the digest GitHub computes for the asset, or a published .sha256 file
+    // Older releases have neither, so their download can't be verified
+    let checksum_url = find_asset(&format!("{}.sha256", modding_tools::ARCHIVE_NAME))
+        .and_then(|a| a.get("browser_download_url"))
+        .and_then(Value::as_str);
+
+    let sha256 = match archive
+        .get("digest")
+        .and_then(Value::as_str)
+        .and_then(|d| d.strip_prefix("sha256:"))
+    {
+        Some(digest) => Some(digest.to_owned()),
+        None => match checksum_url {
+            Some(url) => Some(
+                get(url)?
+                    .text()
+                    .map_err(ReleaseError::Network)?
+                    .split_whitespace()
+                    .next()
+                    .ok_or(ReleaseError::InvalidChecksum)?
+                    .to_owned()
+            ),
+            None => None
+        }
+    };
+
+    if let Some(sha256) = &sha256 {
+        if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ReleaseError::InvalidChecksum);
+        }
+    }
+
+    Ok(Release {
+        version,
+        download_url,
+        sha256: sha256.map(|s| s.to_lowercase())
+    })
+}
+
+impl Release {
+    // Check if the release publishes a checksum to verify the download against
+    pub fn is_verified(&self) -> bool {
+        self.sha256.is_some()
+    }
+
+    // Get the name of the release's partial download, so it's only resumed for the same file
+    fn part_name(&self) -> String {
+        let id = match &self.sha256 {
+            Some(sha256) => sha256.clone(),
+            None => format!("{:x}", Sha256::digest(self.download_url.as_bytes()))
+        };
+
+        format!("{}.part", id)
+    }
+}
+
+// Get the numeric components of a version, ignoring prefixes like "v" and trailing zeros
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let mut components = version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split('.')
+        .map(|c| c.parse::<u64>().ok())
+        .collect::<Option<Vec<u64>>>()?;
+
+    while components.last() == Some(&0) {
+        components.pop();
+    }
+
+    Some(components)
+}
+
+// Check if a release version is newer than the installed one
+// Versions that can't be compared are only newer if they're different
+fn is_newer(release: &str, installed: &str) -> bool {
+    match (parse_version(release), parse_version(installed)) {
+        (Some(release), Some(installed)) => release > installed,
+        _ => release != installed
+    }
+}
+
+// Get the latest release if it's newer than the installed tools
+// Tools with an unknown version are only reported as outdated when asked to
+pub fn check_update(include_unknown: bool) -> Result<Option<Release>, ReleaseError> {
+    let installed = modding_tools::installed_version();
+
+    if installed.is_none() && !include_unknown {
+        return Ok(None);
+    }
+
+    let release = latest()?;
+    let is_update = installed.is_none_or(|v| is_newer(&release.version, &v));
+
+    Ok(is_update.then_some(release))
+}
+
+// Get the folder keeping partial downloads of the modding tools, so they can be resumed
+fn download_dir() -> PathBuf {
+    config::config_dir().join("tools-download")
+}
+
+// Check a finished download against the release's checksum, removing it either way
+fn finish_download(part_path: &Path, release: &Release) -> Result<Vec<u8>, ReleaseError> {
+    let bytes = fs::read(part_path).map_err(ReleaseError::Io)?;
+    let _ = fs::remove_file(part_path);
+
+    if release
+        .sha256
+        .as_ref()
+        .is_some_and(|sha256| format!("{:x}", Sha256::digest(&bytes)) != *sha256)
+    {
+        return Err(ReleaseError::ChecksumMismatch);
+    }
+
+    Ok(bytes)
+}
+
+// Download the release's tools and check them against the published checksum
+// Interrupted and cancelled downloads are resumed the next time the same release is downloaded
+pub fn download(
+    release: &Release, progress: impl Fn(u64, Option<u64>), cancel: &AtomicBool
+) -> Result<Vec<u8>, ReleaseError> {
+    let download_dir = download_dir();
+    fs::create_dir_all(&download_dir).map_err(ReleaseError::Io)?;
+
+    let part_path = download_dir.join(release.part_name());
+
+    // Remove partial downloads of other releases
+    for entry in fs::read_dir(&download_dir)
+        .map_err(ReleaseError::Io)?
+        .filter_map(Result::ok)
+    {
+        if entry.path() != part_path {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part_path)
+        .map_err(ReleaseError::Io)?;
+    let mut downloaded = file.metadata().map_err(ReleaseError::Io)?.len();
+
+    // Ask for the rest of the partial download
+    let mut request = client()?.get(&release.download_url);
+
+    if downloaded > 0 {
+        request = request.header(RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let mut response = match send(request) {
+        Ok(r) => r,
+        Err(ReleaseError::HttpStatus(StatusCode::RANGE_NOT_SATISFIABLE)) if downloaded > 0 => {
+            // The partial download was already complete
+            return finish_download(&part_path, release);
+        },
+        Err(e) => return Err(e)
+    };
+
+    if response.status() != StatusCode::PARTIAL_CONTENT && downloaded > 0 {
+        // The server sent the whole file, start over
+        file.set_len(0).map_err(ReleaseError::Io)?;
+        downloaded = 0;
+    }
+
+    let total = response.content_length().map(|l| l + downloaded);
+    let mut buffer = vec![0; 64 * 1024];
+
+    progress(downloaded, total);
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(ReleaseError::Cancelled);
+        }
+
+        let read = response.read(&mut buffer).map_err(ReleaseError::Interrupted)?;
+
+        if read == 0 {
+            break;
+        }
+
+        file.write_all(&buffer[..read]).map_err(ReleaseError::Io)?;
+        downloaded += read as u64;
+
+        progress(downloaded, total);
+    }
+
+    drop(file);
+    finish_download(&part_path, release)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_versions_are_updates() {
+        assert!(is_newer("v1.10.0", "v1.9.2"));
+        assert!(is_newer("2", "v1.9"));
+        assert!(!is_newer("v1.2", "v1.2.0"));
+        assert!(!is_newer("v1.2.3", "v1.3"));
+    }
+
+    #[test]
+    fn unparsable_versions_are_updates_if_different() {
+        assert!(is_newer("v1.2-beta", "v1.2"));
+        assert!(!is_newer("nightly", "nightly"));
+    }
+}
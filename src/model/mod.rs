@@ -49,13 +49,18 @@ impl Model {
 
     pub fn toggle_all(&self, enable: bool) {
         let imp = self.imp();
-        let borrow = imp.0.write().unwrap();
+        let borrow = imp.0.read().unwrap();
 
         for obj in borrow.iter() {
             obj.set_is_enabled(enable);
         }
     }
 
+    pub fn items(&self) -> Vector<ModData> {
+        let imp = self.imp();
+        imp.0.read().unwrap().clone()
+    }
+
     pub fn remove(&self, index: u32) {
         let imp = self.imp();
         imp.0.write().unwrap().remove(index as usize);
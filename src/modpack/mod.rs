@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::Path,
+    result
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    archive::{self, ArchiveError},
+    eternal_mod, hash, injector_settings, profiles
+};
+
+// Modpack manifest format version supported by this build
+const MANIFEST_FORMAT_VERSION: u64 = 1;
+
+// Modpack manifest, stored as manifest.json
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u64,
+    mods: Vec<ManifestMod>,
+    #[serde(default)]
+    injector_settings: BTreeMap<String, String>
+}
+
+// Mod bundled in a modpack, stored under mods/
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestMod {
+    filename: String,
+    sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    load_priority: Option<i64>
+}
+
+// Error found while exporting or importing a modpack
+#[derive(Debug)]
+pub enum ModpackError {
+    Io(io::Error),
+    Zip(ZipError),
+    Archive(ArchiveError),
+    Json(serde_json::Error),
+    UnsupportedVersion(u64),
+    InvalidFilename(String),
+    MissingMod(String),
+    HashMismatch(String)
+}
+
+impl fmt::Display for ModpackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModpackError::Io(e) => write!(f, "{}", e),
+            ModpackError::Zip(e) => write!(f, "{}", e),
+            ModpackError::Archive(e) => write!(f, "{}", e),
+            ModpackError::Json(e) => write!(f, "Failed to parse the modpack manifest: {}", e),
+            ModpackError::UnsupportedVersion(v) => write!(
+                f,
+                "Unsupported modpack version {} (expected {}).",
+                v, MANIFEST_FORMAT_VERSION
+            ),
+            ModpackError::InvalidFilename(n) => write!(f, "Invalid mod filename \"{}\".", n),
+            ModpackError::MissingMod(n) => write!(f, "The modpack doesn't contain \"{}\".", n),
+            ModpackError::HashMismatch(n) => write!(f, "\"{}\" doesn't match the modpack's hash.", n)
+        }
+    }
+}
+
+impl From<io::Error> for ModpackError {
+    fn from(e: io::Error) -> ModpackError {
+        ModpackError::Io(e)
+    }
+}
+
+impl From<ZipError> for ModpackError {
+    fn from(e: ZipError) -> ModpackError {
+        ModpackError::Zip(e)
+    }
+}
+
+// Result of importing a modpack
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub installed: usize,
+    pub disabled: usize,
+    pub kept: Vec<String>,
+    pub failed: Vec<String>,
+    pub skipped_settings: Vec<String>,
+    pub settings_saved: bool
+}
+
+// Check if a filename from a manifest can be used in the mods folder
+fn is_valid_filename(filename: &str) -> bool {
+    !filename.is_empty()
+        && !filename.starts_with('.')
+        && !filename.contains(['/', '\\', ':'])
+        && Path::new(filename).file_name().is_some_and(|f| f == filename)
+}
+
+// Export the enabled mods and the injector settings into a modpack
+pub fn export(path: &Path) -> result::Result<usize, ModpackError> {
+    let mods_folder = crate::game_path().join("Mods");
+    let mod_paths = profiles::mod_files(&mods_folder);
+
+    // Create manifest
+    let mut manifest = Manifest {
+        version: MANIFEST_FORMAT_VERSION,
+        mods: Vec::with_capacity(mod_paths.len()),
+        injector_settings: injector_settings::read_user_settings()
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    };
+
+    for mod_path in &mod_paths {
+        manifest.mods.push(ManifestMod {
+            filename: mod_path.file_name().unwrap().to_string_lossy().into_owned(),
+            sha256: hash::hash_file(mod_path)?,
+            load_priority: eternal_mod::read_eternal_mod(mod_path)
+                .ok()
+                .and_then(|m| m.load_priority)
+        });
+    }
+
+    // Write manifest and mods, storing the mods as-is since they're already compressed
+    let mut writer = ZipWriter::new(File::create(path)?);
+
+    writer.start_file(
+        "manifest.json",
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+    )?;
+    writer.write_all(&serde_json::to_vec_pretty(&manifest).map_err(ModpackError::Json)?)?;
+
+    for (mod_path, manifest_mod) in mod_paths.iter().zip(&manifest.mods) {
+        writer.start_file(
+            format!("mods/{}", manifest_mod.filename),
+            SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .large_file(fs::metadata(mod_path)?.len() >= u32::MAX as u64)
+        )?;
+        io::copy(&mut File::open(mod_path)?, &mut writer)?;
+    }
+
+    writer.finish()?;
+
+    Ok(manifest.mods.len())
+}
+
+// Open a modpack and read its manifest
+fn open(path: &Path) -> result::Result<(ZipArchive<File>, Manifest), ModpackError> {
+    let mut modpack_zip = archive::open(path).map_err(ModpackError::Archive)?;
+
+    // Read manifest
+    let manifest: Manifest = {
+        let mut buffer = Vec::new();
+        modpack_zip.by_name("manifest.json")?.read_to_end(&mut buffer)?;
+        serde_json::from_slice(&buffer).map_err(ModpackError::Json)?
+    };
+
+    if manifest.version != MANIFEST_FORMAT_VERSION {
+        return Err(ModpackError::UnsupportedVersion(manifest.version));
+    }
+
+    for (i, manifest_mod) in manifest.mods.iter().enumerate() {
+        if !is_valid_filename(&manifest_mod.filename)
+            || manifest.mods[..i]
+                .iter()
+                .any(|m| m.filename == manifest_mod.filename)
+        {
+            return Err(ModpackError::InvalidFilename(manifest_mod.filename.clone()));
+        }
+    }
+
+    Ok((modpack_zip, manifest))
+}
+
+// Check if an installed mod is the same file as the modpack's
+fn is_installed(mod_path: &Path, manifest_mod: &ManifestMod) -> bool {
+    hash::hash_file(mod_path).is_ok_and(|h| h == manifest_mod.sha256.to_lowercase())
+}
+
+// Get the modpack's mods that would replace a different installed mod with the same name
+pub fn replaced_mods(path: &Path) -> result::Result<Vec<String>, ModpackError> {
+    let (_, manifest) = open(path)?;
+    let mods_folder = crate::game_path().join("Mods");
+
+    Ok(manifest
+        .mods
+        .iter()
+        .filter(|m| {
+            let mod_path = mods_folder.join(&m.filename);
+            mod_path.is_file() && !is_installed(&mod_path, m)
+        })
+        .map(|m| m.filename.clone())
+        .collect())
+}
+
+// Import a modpack, enabling only its mods and applying its injector settings
+// Installed mods with the same name as one of the modpack's are only replaced if asked to
+pub fn import(path: &Path, replace: bool) -> result::Result<ImportReport, ModpackError> {
+    let (mut modpack_zip, manifest) = open(path)?;
+    let mut report = ImportReport::default();
+
+    // Extract and verify every mod before changing anything
+    let mods_folder = crate::game_path().join("Mods");
+    let mut extracted = Vec::new();
+
+    for manifest_mod in &manifest.mods {
+        let mod_path = mods_folder.join(&manifest_mod.filename);
+
+        // Leave the mods that are already installed alone
+        if mod_path.is_file() {
+            if is_installed(&mod_path, manifest_mod) {
+                report.installed += 1;
+                continue;
+            }
+
+            if !replace {
+                report.kept.push(manifest_mod.filename.clone());
+                continue;
+            }
+        }
+
+        let temp_path = mods_folder.join(format!(".{}.tmp", manifest_mod.filename));
+        extracted.push((temp_path.clone(), manifest_mod));
+
+        if let Err(e) = extract_mod(&mut modpack_zip, manifest_mod, &temp_path) {
+            for (temp_path, _) in &extracted {
+                let _ = fs::remove_file(temp_path);
+            }
+
+            return Err(e);
+        }
+    }
+
+    // Disable mods that aren't in the modpack
+    let disabled_mods_folder = crate::game_path().join("DisabledMods");
+
+    for mod_path in profiles::mod_files(&mods_folder) {
+        let filename = mod_path.file_name().unwrap().to_string_lossy().into_owned();
+
+        if manifest.mods.iter().any(|m| m.filename == filename) {
+            continue;
+        }
+
+        let disabled_path = disabled_mods_folder.join(&filename);
+
+        if disabled_path.exists() || fs::rename(&mod_path, &disabled_path).is_err() {
+            report.failed.push(filename);
+            continue;
+        }
+
+        report.disabled += 1;
+    }
+
+    // Install the modpack's mods
+    for (temp_path, manifest_mod) in &extracted {
+        if fs::rename(temp_path, mods_folder.join(&manifest_mod.filename)).is_err() {
+            let _ = fs::remove_file(temp_path);
+            report.failed.push(manifest_mod.filename.clone());
+            continue;
+        }
+
+        // Remove identical disabled copies, so the mod isn't listed twice
+        let disabled_path = disabled_mods_folder.join(&manifest_mod.filename);
+
+        if disabled_path.is_file() && is_installed(&disabled_path, manifest_mod) {
+            let _ = fs::remove_file(&disabled_path);
+        }
+
+        report.installed += 1;
+    }
+
+    // Apply the injector settings users can change, like the export does
+    let mut settings = Vec::new();
+
+    for (key, value) in manifest.injector_settings {
+        if injector_settings::is_user_setting(&key) {
+            settings.push((key, value));
+        }
+        else {
+            report.skipped_settings.push(key);
+        }
+    }
+
+    report.settings_saved = settings.is_empty() || injector_settings::write_settings(&settings);
+
+    Ok(report)
+}
+
+// Extract a mod from a modpack, checking its hash
+fn extract_mod(
+    modpack_zip: &mut ZipArchive<File>, manifest_mod: &ManifestMod, path: &Path
+) -> result::Result<(), ModpackError> {
+    let mod_file = match modpack_zip.by_name(&format!("mods/{}", manifest_mod.filename)) {
+        Ok(f) => f,
+        Err(ZipError::FileNotFound) => return Err(ModpackError::MissingMod(manifest_mod.filename.clone())),
+        Err(e) => return Err(e.into())
+    };
+
+    let mut output = File::create(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 65536];
+
+    // Don't trust the declared size
+    let size = mod_file.size();
+    let mut mod_file = mod_file.take(size + 1);
+    let mut written = 0;
+
+    // Write and hash the mod at the same time
+    loop {
+        let read = mod_file.read(&mut buffer)?;
+
+        if read == 0 {
+            break;
+        }
+
+        written += read as u64;
+
+        if written > size {
+            return Err(ModpackError::Archive(ArchiveError::SizeMismatch(
+                manifest_mod.filename.clone()
+            )));
+        }
+
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+    }
+
+    output.sync_all()?;
+
+    if format!("{:x}", hasher.finalize()) != manifest_mod.sha256.to_lowercase() {
+        return Err(ModpackError::HashMismatch(manifest_mod.filename.clone()));
+    }
+
+    Ok(())
+}
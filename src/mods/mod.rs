@@ -0,0 +1,202 @@
+use std::{
+    fs,
+    io::{self, ErrorKind, Read},
+    path::{Path, PathBuf}
+};
+
+use im::Vector;
+
+use crate::{
+    archive,
+    conflicts::{is_game_file_entry, ConflictIndex},
+    eternal_mod::EternalMod,
+    injector, injector_settings,
+    mod_data::ModData,
+    online_safety::is_mod_online_safe
+};
+
+// Check if a folder is a DOOM Eternal installation
+pub fn is_game_folder(path: &Path) -> bool {
+    path.is_dir() && path.join("DOOMEternalx64vk.exe").is_file()
+}
+
+// Load a mod into the list
+fn load_mod(
+    mod_path: PathBuf, enabled: bool, only_load_online_safe: bool, loader_version: Option<u32>,
+    mod_list: &mut Vector<ModData>
+) {
+    let file_name = mod_path.file_name().unwrap().to_str().unwrap();
+    let mod_data: ModData;
+
+    // Skip hidden files, such as mods being rewritten
+    if file_name.starts_with('.') {
+        return;
+    }
+
+    // Get mod data, rejecting archives that aren't safe to extract
+    match archive::open(&mod_path) {
+        Ok(mut zip_file) => {
+            // Check if mod is online safe
+            let online_safety = is_mod_online_safe(&mut zip_file);
+
+            // Read properties from EternalMod.json
+            let (eternal_mod, warnings) = match zip_file.by_name("EternalMod.json") {
+                Ok(mut eternal_mod_file) => {
+                    let mut buffer = Vec::new();
+
+                    match eternal_mod_file.read_to_end(&mut buffer) {
+                        Ok(_) => EternalMod::from_slice(&buffer),
+                        Err(e) => (
+                            EternalMod::default(),
+                            vec![format!("Failed to read EternalMod.json: {}.", e)]
+                        )
+                    }
+                },
+                Err(_) => (EternalMod::default(), Vec::new())
+            };
+
+            mod_data = ModData::new(
+                file_name,
+                None,
+                enabled,
+                Some(&online_safety),
+                only_load_online_safe,
+                &eternal_mod,
+                &warnings,
+                loader_version
+            );
+
+            // Save modified game files for conflict detection
+            mod_data.set_entries(
+                zip_file
+                    .file_names()
+                    .filter(|f| is_game_file_entry(f))
+                    .map(|f| f.to_owned())
+                    .collect()
+            );
+        },
+        Err(e) => {
+            mod_data = ModData::new(
+                file_name,
+                Some(&e.to_string()),
+                enabled,
+                None,
+                only_load_online_safe,
+                &EternalMod::default(),
+                &[],
+                loader_version
+            );
+        }
+    }
+
+    // Add to list
+    mod_list.push_back(mod_data);
+}
+
+// Get all enabled and disabled mods, sorted by filename
+pub fn scan() -> Vector<ModData> {
+    // Buffer to store newly loaded mods
+    let mut buffer_mod_list = Vector::new();
+
+    // Check if only online safe mods should be loaded
+    let only_load_online_safe = injector_settings::load().is_some_and(|s| s.get("ONLINE_SAFE") == Some("1"));
+
+    // Get installed mod loader version
+    let loader_version = injector::installed_version();
+
+    // Get enabled mods
+    for mod_file in fs::read_dir(crate::game_path().join("Mods"))
+        .unwrap()
+        .filter_map(|f| f.ok())
+    {
+        load_mod(
+            mod_file.path(),
+            true,
+            only_load_online_safe,
+            loader_version,
+            &mut buffer_mod_list
+        );
+    }
+
+    // Get disabled mods
+    for mod_file in fs::read_dir(crate::game_path().join("DisabledMods"))
+        .unwrap()
+        .filter_map(|f| f.ok())
+    {
+        load_mod(
+            mod_file.path(),
+            false,
+            only_load_online_safe,
+            loader_version,
+            &mut buffer_mod_list
+        );
+    }
+
+    // Sort buffer mod list
+    buffer_mod_list.sort_by(|a, b| {
+        a.filename()
+            .unwrap()
+            .to_lowercase()
+            .cmp(&b.filename().unwrap().to_lowercase())
+    });
+
+    // Find conflicts between enabled mods
+    let conflict_index = ConflictIndex::build(&buffer_mod_list);
+
+    for mod_data in &buffer_mod_list {
+        mod_data.set_conflicts(conflict_index.conflict_count(&mod_data.filename().unwrap()));
+    }
+
+    buffer_mod_list
+}
+
+// Enable or disable a mod by moving it between the mod folders
+pub fn set_enabled(filename: &str, enabled: bool) -> io::Result<()> {
+    // Get mod paths
+    let enabled_mod_path = crate::game_path().join("Mods").join(filename);
+    let disabled_mod_path = crate::game_path().join("DisabledMods").join(filename);
+
+    if enabled {
+        // Move to enabled folder
+        fs::rename(disabled_mod_path, enabled_mod_path)
+    }
+    else {
+        // Move to disabled folder
+        fs::rename(enabled_mod_path, disabled_mod_path)
+    }
+}
+
+// Get the path a mod would be installed to
+// Returns None if it isn't a zip file or is already in the mods folder
+pub fn install_path(path: &Path) -> Option<PathBuf> {
+    // Make sure file exists and is a zip
+    if !path.is_file() || path.extension().is_none_or(|e| e != "zip") {
+        return None;
+    }
+
+    let mods_folder = crate::game_path().join("Mods");
+
+    // Check if it's already in the target folder
+    let parent = path.parent().unwrap_or_else(|| Path::new("")).canonicalize().ok();
+
+    if parent.is_none() || parent == mods_folder.canonicalize().ok() {
+        return None;
+    }
+
+    Some(mods_folder.join(path.file_name()?))
+}
+
+// Install a mod by copying it to the mods folder
+pub fn install(path: &Path) -> io::Result<PathBuf> {
+    let new_path = install_path(path).ok_or_else(|| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            "not a .zip file, or already in the mods folder"
+        )
+    })?;
+
+    // Copy file to Mods folder
+    fs::copy(path, &new_path)?;
+
+    Ok(new_path)
+}
@@ -0,0 +1,443 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Read, Seek},
+    path::{Path, PathBuf},
+    result,
+    sync::Mutex
+};
+
+use gtk::glib;
+use once_cell::sync::OnceCell;
+use serde_json::{Result, Value};
+use zip::ZipArchive;
+
+// Default online safety rules shipped with the app
+static DEFAULT_RULES: &str = include_str!("rules.json");
+
+// Rules file format version supported by this build
+const RULES_FORMAT_VERSION: u64 = 1;
+
+// Online safety rules in use
+static RULES: OnceCell<OnlineSafetyRules> = OnceCell::new();
+
+// Error found in the user's rules file, until it's reported
+static RULES_ERROR: Mutex<Option<RulesError>> = Mutex::new(None);
+
+// Set of rules used to decide if a mod is safe for online play
+#[derive(Debug)]
+pub struct OnlineSafetyRules {
+    pub unsafe_resource_keywords: Vec<String>,
+    pub online_safe_keywords: Vec<String>
+}
+
+// Error found while parsing a rules file
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u64),
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    InvalidKeyword(&'static str, usize)
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Io(e) => write!(f, "Failed to read the rules file: {}", e),
+            RulesError::Json(e) => write!(f, "Failed to parse the rules file: {}", e),
+            RulesError::UnsupportedVersion(v) => write!(
+                f,
+                "Unsupported rules file version {} (expected {}).",
+                v, RULES_FORMAT_VERSION
+            ),
+            RulesError::MissingField(k) => write!(f, "Missing field \"{}\".", k),
+            RulesError::InvalidField(k) => write!(f, "Field \"{}\" has the wrong type.", k),
+            RulesError::InvalidKeyword(k, i) => write!(
+                f,
+                "Entry {} of \"{}\" must be a non-empty lowercase string.",
+                i, k
+            )
+        }
+    }
+}
+
+impl OnlineSafetyRules {
+    // Parse and validate rules from JSON
+    pub fn parse(json: &str) -> result::Result<OnlineSafetyRules, RulesError> {
+        let rules: Value = serde_json::from_str(json).map_err(RulesError::Json)?;
+
+        // Check format version
+        let version = rules
+            .get("version")
+            .ok_or(RulesError::MissingField("version"))?
+            .as_u64()
+            .ok_or(RulesError::InvalidField("version"))?;
+
+        if version != RULES_FORMAT_VERSION {
+            return Err(RulesError::UnsupportedVersion(version));
+        }
+
+        // Check rules revision
+        if rules.get("revision").is_some_and(|r| !r.is_u64()) {
+            return Err(RulesError::InvalidField("revision"));
+        }
+
+        Ok(OnlineSafetyRules {
+            unsafe_resource_keywords: parse_keywords(&rules, "unsafeResourceKeywords")?,
+            online_safe_keywords: parse_keywords(&rules, "onlineSafeKeywords")?
+        })
+    }
+
+    // Get the rules shipped with the app
+    pub fn default_rules() -> OnlineSafetyRules {
+        OnlineSafetyRules::parse(DEFAULT_RULES).expect("Default online safety rules are invalid")
+    }
+
+    // Check if the resource can't be modified for online play
+    fn is_unsafe_resource(&self, resource_name: &str) -> bool {
+        self.unsafe_resource_keywords
+            .iter()
+            .any(|k| resource_name.starts_with(k.as_str()))
+    }
+
+    // Check if the file can be modified in online-unsafe resources
+    fn is_whitelisted(&self, mod_name: &str) -> bool {
+        self.online_safe_keywords
+            .iter()
+            .any(|k| mod_name.contains(k.as_str()))
+    }
+}
+
+// Parse a list of keywords from the rules JSON
+fn parse_keywords(rules: &Value, key: &'static str) -> result::Result<Vec<String>, RulesError> {
+    let array = rules
+        .get(key)
+        .ok_or(RulesError::MissingField(key))?
+        .as_array()
+        .ok_or(RulesError::InvalidField(key))?;
+
+    let mut keywords = Vec::with_capacity(array.len());
+
+    for (i, keyword) in array.iter().enumerate() {
+        // Keywords are matched against lowercase paths
+        match keyword.as_str() {
+            Some(k) if !k.is_empty() && k == k.to_lowercase() => keywords.push(k.to_owned()),
+            _ => return Err(RulesError::InvalidKeyword(key, i))
+        }
+    }
+
+    Ok(keywords)
+}
+
+// Get the path of the user's rules file
+pub fn user_rules_path() -> PathBuf {
+    glib::user_config_dir()
+        .join("EternalModManager")
+        .join("online-safety-rules.json")
+}
+
+// Load the online safety rules, preferring the user's rules file
+// Falls back to the default rules if the user's file can't be used
+fn load_rules() -> OnlineSafetyRules {
+    let user_rules_path = user_rules_path();
+
+    // Use default rules if there's no user rules file
+    if !user_rules_path.is_file() {
+        return OnlineSafetyRules::default_rules();
+    }
+
+    // Load user rules
+    match fs::read_to_string(&user_rules_path)
+        .map_err(RulesError::Io)
+        .and_then(|r| OnlineSafetyRules::parse(&r))
+    {
+        Ok(rules) => rules,
+        Err(e) => {
+            // Keep the error to be reported by init_rules
+            *RULES_ERROR.lock().unwrap() = Some(e);
+            OnlineSafetyRules::default_rules()
+        }
+    }
+}
+
+// Load the online safety rules if they weren't loaded yet
+// Returns the error found in the user's file if it had to be ignored, only once
+pub fn init_rules() -> Option<RulesError> {
+    rules();
+    RULES_ERROR.lock().unwrap().take()
+}
+
+// Get the online safety rules in use
+pub fn rules() -> &'static OnlineSafetyRules {
+    RULES.get_or_init(load_rules)
+}
+
+// Rule broken by an online-unsafe mod file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsafeReason {
+    UnsafeContainer,
+    LwoFile,
+    NonWhitelistedDecl,
+    AssetsInfoResources
+}
+
+impl fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            UnsafeReason::UnsafeContainer => "modifies a file in an online-unsafe resource",
+            UnsafeReason::LwoFile => ".lwo files can't be modified in online-unsafe resources",
+            UnsafeReason::NonWhitelistedDecl => "decl is not in the online-safe whitelist",
+            UnsafeReason::AssetsInfoResources => {
+                "AssetsInfo JSON injects resources into an online-unsafe resource"
+            },
+        };
+
+        write!(f, "{}", description)
+    }
+}
+
+// Mod file that breaks an online safety rule
+#[derive(Clone, Debug)]
+pub struct UnsafeEntry {
+    pub path: String,
+    pub reason: UnsafeReason
+}
+
+impl fmt::Display for UnsafeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+// Result of checking a mod for online safety
+#[derive(Clone, Debug, Default)]
+pub struct OnlineSafetyVerdict {
+    pub unsafe_entries: Vec<UnsafeEntry>
+}
+
+impl OnlineSafetyVerdict {
+    // Check if no rules were broken
+    pub fn is_safe(&self) -> bool {
+        self.unsafe_entries.is_empty()
+    }
+
+    // Get offending entries as a multi-line string
+    pub fn details(&self) -> String {
+        self.unsafe_entries
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn push(&mut self, path: &str, reason: UnsafeReason) {
+        self.unsafe_entries.push(UnsafeEntry {
+            path: path.to_owned(),
+            reason
+        });
+    }
+}
+
+// Check if mod is safe for online play
+pub fn is_mod_online_safe(mod_zip: &mut ZipArchive<File>) -> OnlineSafetyVerdict {
+    let sound_folder = crate::game_path()
+        .join("base")
+        .join("sound")
+        .join("soundbanks")
+        .join("pc");
+
+    check_mod(rules(), mod_zip, &sound_folder)
+}
+
+// Check a mod against the rules, allowing the sound containers in the sound folder
+fn check_mod<R: Read + Seek>(
+    rules: &OnlineSafetyRules, mod_zip: &mut ZipArchive<R>, sound_folder: &Path
+) -> OnlineSafetyVerdict {
+    let mut verdict = OnlineSafetyVerdict::default();
+    let mut assets_info_jsons = Vec::new();
+
+    // Iterate through zip's entries
+    for mod_file in mod_zip.file_names() {
+        let mod_file_entry = mod_file.to_lowercase();
+
+        // Skip directories
+        if mod_file_entry.ends_with('/') {
+            continue;
+        }
+
+        // Skip top-level files
+        if !mod_file_entry.contains('/') {
+            continue;
+        }
+
+        // Allow hidden system files
+        if mod_file_entry.ends_with("desktop.ini") || mod_file_entry.ends_with(".ds_store") {
+            continue;
+        }
+
+        let container_name = mod_file_entry.split('/').next().unwrap();
+        let mod_name = &mod_file_entry[container_name.len() + 1..];
+        let sound_container_path = sound_folder.join(format!("{}.snd", container_name));
+
+        // Allow sound files
+        if sound_container_path.is_file() {
+            continue;
+        }
+
+        // Allow streamdb mods
+        if container_name == "streamdb" {
+            continue;
+        }
+
+        // Save AssetsInfo JSON files to be handled later
+        if mod_file_entry.starts_with("eternalmod/assetsinfo") && mod_file_entry.ends_with(".json") {
+            assets_info_jsons.push(mod_file.to_owned());
+        }
+
+        // Check if mod is modifying an online-unsafe resource
+        let is_modifying_unsafe_resource = rules.is_unsafe_resource(container_name);
+
+        // Files with .lwo extension are unsafe, including suffixed ones like "model.lwo$uvlayout_lightmap=1"
+        if PathBuf::from(&mod_file_entry)
+            .extension()
+            .is_some_and(|e| e.to_string_lossy().starts_with("lwo"))
+            && is_modifying_unsafe_resource
+        {
+            verdict.push(mod_file, UnsafeReason::LwoFile);
+            continue;
+        }
+
+        // Allow modification of everything outside of generated/decls, except .entities files
+        if !mod_name.starts_with("generated/decls") && !mod_name.ends_with(".entities") {
+            continue;
+        }
+
+        // Do not allow mods to modify non-whitelisted files in unsafe resources
+        if !rules.is_whitelisted(mod_name) && is_modifying_unsafe_resource {
+            let reason = if mod_name.starts_with("generated/decls") {
+                UnsafeReason::NonWhitelistedDecl
+            }
+            else {
+                UnsafeReason::UnsafeContainer
+            };
+
+            verdict.push(mod_file, reason);
+        }
+    }
+
+    // Don't allow injecting files into the online-unsafe resources
+    for assets_info_entry in assets_info_jsons {
+        // Get the resource name from the JSON's file name
+        let lowercase_entry = assets_info_entry.to_lowercase();
+        let resource_name = lowercase_entry
+            .rsplit('/')
+            .next()
+            .unwrap()
+            .trim_end_matches(".json");
+
+        // Unzip and deserialize JSON
+        if let Ok(assets_info_file) = mod_zip.by_name(&assets_info_entry) {
+            let deserialize: Result<Value> = serde_json::from_reader(assets_info_file);
+
+            if let Ok(assets_info) = deserialize {
+                if assets_info["resources"].is_array()
+                    && !assets_info["resources"].as_array().unwrap().is_empty()
+                    && rules.is_unsafe_resource(resource_name)
+                {
+                    verdict.push(&assets_info_entry, UnsafeReason::AssetsInfoResources);
+                }
+            }
+        }
+    }
+
+    verdict
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    // Check a mod made of the given files and contents
+    fn check_files(files: &[(&str, &str)]) -> OnlineSafetyVerdict {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (name, contents) in files {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+
+        let mut mod_zip = ZipArchive::new(writer.finish().unwrap()).unwrap();
+        check_mod(&OnlineSafetyRules::default_rules(), &mut mod_zip, Path::new(""))
+    }
+
+    // Get the reasons a mod was flagged for
+    fn reasons(files: &[(&str, &str)]) -> Vec<UnsafeReason> {
+        check_files(files)
+            .unsafe_entries
+            .into_iter()
+            .map(|e| e.reason)
+            .collect()
+    }
+
+    #[test]
+    fn lwo_files_in_unsafe_resources_are_flagged() {
+        assert_eq!(
+            reasons(&[("gameresources/art/model.lwo", "")]),
+            vec![UnsafeReason::LwoFile]
+        );
+        assert_eq!(
+            reasons(&[("gameresources/art/model.lwo$uvlayout_lightmap=1", "")]),
+            vec![UnsafeReason::LwoFile]
+        );
+    }
+
+    #[test]
+    fn lwo_files_in_safe_resources_are_allowed() {
+        assert!(check_files(&[("e1m1_intro/art/model.lwo$uvlayout_lightmap=1", "")]).is_safe());
+        assert!(check_files(&[("gameresources/art/model.lwofile.tga", "")]).is_safe());
+    }
+
+    #[test]
+    fn non_whitelisted_decls_are_flagged() {
+        assert_eq!(
+            reasons(&[("gameresources/generated/decls/weapon/shotgun.decl", "")]),
+            vec![UnsafeReason::NonWhitelistedDecl]
+        );
+        assert!(check_files(&[("gameresources/generated/decls/fx/shotgun.decl", "")]).is_safe());
+        assert!(check_files(&[("e1m1_intro/generated/decls/weapon/shotgun.decl", "")]).is_safe());
+    }
+
+    #[test]
+    fn assets_info_adding_to_unsafe_resources_is_flagged() {
+        let assets_info = r#"{ "resources": [{ "name": "pvp_shell" }] }"#;
+
+        let verdict = check_files(&[("EternalMod/AssetsInfo/gameresources_patch1.json", assets_info)]);
+        assert_eq!(verdict.unsafe_entries.len(), 1);
+        assert_eq!(
+            verdict.unsafe_entries[0].path,
+            "EternalMod/AssetsInfo/gameresources_patch1.json"
+        );
+        assert_eq!(
+            verdict.unsafe_entries[0].reason,
+            UnsafeReason::AssetsInfoResources
+        );
+    }
+
+    #[test]
+    fn assets_info_adding_to_safe_resources_is_allowed() {
+        let assets_info = r#"{ "resources": [{ "name": "e1m1_intro" }] }"#;
+
+        assert!(check_files(&[("EternalMod/AssetsInfo/e1m1_intro.json", assets_info)]).is_safe());
+        assert!(check_files(&[(
+            "EternalMod/AssetsInfo/gameresources.json",
+            r#"{ "resources": [] }"#
+        )])
+        .is_safe());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf}
+};
+
+use gtk::glib::KeyFile;
+
+use crate::{config, hash::hash_file, injector_settings};
+
+// Prefix of the config file groups storing profiles
+const PROFILE_GROUP_PREFIX: &str = "profile ";
+
+// Mod file saved in a profile
+#[derive(Clone, Debug)]
+pub struct ProfileMod {
+    pub filename: String,
+    pub hash: String
+}
+
+// Named set of enabled mods and injector settings
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub mods: Vec<ProfileMod>,
+    pub injector_settings: Vec<(String, String)>
+}
+
+// Result of applying a profile
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    pub enabled: usize,
+    pub disabled: usize,
+    pub missing: Vec<String>,
+    pub changed: Vec<String>,
+    pub failed: Vec<String>,
+    pub settings_saved: bool
+}
+
+impl ApplyReport {
+    // Check if the profile was fully applied
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.failed.is_empty() && self.settings_saved
+    }
+}
+
+// Check if the name can be used for a profile
+pub fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['[', ']', '\n', '\r'])
+}
+
+// Get the mod files in a mods folder
+pub fn mod_files(folder: &Path) -> Vec<PathBuf> {
+    let mut files = match fs::read_dir(folder) {
+        Ok(dir) => dir
+            .filter_map(|f| f.ok())
+            .map(|f| f.path())
+            .filter(|p| p.is_file())
+            .filter(|p| !p.file_name().unwrap().to_string_lossy().starts_with('.'))
+            .collect::<Vec<PathBuf>>(),
+        Err(_) => Vec::new()
+    };
+
+    files.sort();
+    files
+}
+
+impl Profile {
+    // Create a profile from the currently enabled mods and injector settings
+    pub fn capture(name: &str) -> io::Result<Profile> {
+        let mods_folder = crate::game_path().join("Mods");
+        let mut mods = Vec::new();
+
+        for mod_path in mod_files(&mods_folder) {
+            mods.push(ProfileMod {
+                filename: mod_path.file_name().unwrap().to_string_lossy().into_owned(),
+                hash: hash_file(&mod_path)?
+            });
+        }
+
+        Ok(Profile {
+            name: name.trim().to_owned(),
+            mods,
+            injector_settings: injector_settings::read_user_settings().unwrap_or_default()
+        })
+    }
+
+    // Read a profile from the config file
+    // Mods are stored one per line as "<hash> <filename>", settings as "<key>=<value>"
+    fn from_keyfile(keyfile: &KeyFile, group: &str) -> Option<Profile> {
+        let name = group.strip_prefix(PROFILE_GROUP_PREFIX)?;
+
+        let mods = keyfile
+            .string(group, "mods")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| l.split_once(' '))
+            .map(|(h, f)| ProfileMod {
+                filename: f.to_owned(),
+                hash: h.to_owned()
+            })
+            .collect();
+
+        let injector_settings = keyfile
+            .string(group, "injector-settings")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| l.split_once('='))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        Some(Profile {
+            name: name.to_owned(),
+            mods,
+            injector_settings
+        })
+    }
+
+    // Write the profile into the config file
+    fn to_keyfile(&self, keyfile: &KeyFile) {
+        let group = format!("{}{}", PROFILE_GROUP_PREFIX, self.name);
+
+        let mods = self
+            .mods
+            .iter()
+            .map(|m| format!("{} {}", m.hash, m.filename))
+            .collect::<Vec<String>>();
+
+        let settings = self
+            .injector_settings
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>();
+
+        keyfile.set_string(&group, "mods", &mods.join("\n"));
+        keyfile.set_string(&group, "injector-settings", &settings.join("\n"));
+    }
+
+    // Enable the profile's mods, disable the rest and apply its injector settings
+    pub fn apply(&self) -> ApplyReport {
+        let mods_folder = crate::game_path().join("Mods");
+        let disabled_mods_folder = crate::game_path().join("DisabledMods");
+        let mut report = ApplyReport::default();
+
+        // Mods that must stay in the mods folder
+        let mut kept_mods = Vec::new();
+
+        // Hashes of all mods, only computed if a mod has to be found by content
+        let mut hashed_mods: Option<Vec<(PathBuf, String)>> = None;
+
+        for profile_mod in &self.mods {
+            let enabled_path = mods_folder.join(&profile_mod.filename);
+            let disabled_path = disabled_mods_folder.join(&profile_mod.filename);
+
+            // Find mod by filename
+            let found_path = if enabled_path.is_file() {
+                Some(enabled_path.clone())
+            }
+            else if disabled_path.is_file() {
+                Some(disabled_path.clone())
+            }
+            else {
+                None
+            };
+
+            if let Some(path) = found_path {
+                // Check if the mod changed since the profile was saved
+                if hash_file(&path).ok().as_ref() != Some(&profile_mod.hash) {
+                    report.changed.push(profile_mod.filename.clone());
+                }
+
+                if path == disabled_path {
+                    if enabled_path.exists() || fs::rename(&disabled_path, &enabled_path).is_err() {
+                        report.failed.push(profile_mod.filename.clone());
+                        continue;
+                    }
+
+                    report.enabled += 1;
+                }
+
+                kept_mods.push(enabled_path);
+                continue;
+            }
+
+            // Find renamed mods by content
+            let hashed_mods = hashed_mods.get_or_insert_with(|| {
+                mod_files(&mods_folder)
+                    .into_iter()
+                    .chain(mod_files(&disabled_mods_folder))
+                    .filter_map(|p| hash_file(&p).ok().map(|h| (p, h)))
+                    .collect()
+            });
+
+            match hashed_mods.iter().find(|(_, h)| *h == profile_mod.hash) {
+                Some((path, _)) if path.starts_with(&mods_folder) => kept_mods.push(path.clone()),
+                Some((path, _)) => {
+                    let new_path = mods_folder.join(path.file_name().unwrap());
+
+                    if new_path.exists() || fs::rename(path, &new_path).is_err() {
+                        report.failed.push(profile_mod.filename.clone());
+                        continue;
+                    }
+
+                    report.enabled += 1;
+                    kept_mods.push(new_path);
+                },
+                None => report.missing.push(profile_mod.filename.clone())
+            }
+        }
+
+        // Disable mods that aren't in the profile
+        for mod_path in mod_files(&mods_folder) {
+            if kept_mods.contains(&mod_path) {
+                continue;
+            }
+
+            let disabled_path = disabled_mods_folder.join(mod_path.file_name().unwrap());
+
+            if disabled_path.exists() || fs::rename(&mod_path, &disabled_path).is_err() {
+                report
+                    .failed
+                    .push(mod_path.file_name().unwrap().to_string_lossy().into_owned());
+                continue;
+            }
+
+            report.disabled += 1;
+        }
+
+        // Apply injector settings
+        report.settings_saved =
+            self.injector_settings.is_empty() || injector_settings::write_settings(&self.injector_settings);
+
+        report
+    }
+}
+
+// Get all saved profiles
+pub fn list() -> Vec<Profile> {
+    let keyfile = config::load_user();
+
+    keyfile
+        .groups()
+        .iter()
+        .filter_map(|g| Profile::from_keyfile(&keyfile, g))
+        .collect()
+}
+
+// Save a profile, replacing any profile with the same name
+pub fn save(profile: &Profile) -> bool {
+    let keyfile = config::load_user();
+    let _ = keyfile.remove_group(&format!("{}{}", PROFILE_GROUP_PREFIX, profile.name));
+    profile.to_keyfile(&keyfile);
+
+    config::save(&keyfile)
+}
+
+// Delete a saved profile
+pub fn delete(name: &str) -> bool {
+    let keyfile = config::load_user();
+
+    if keyfile
+        .remove_group(&format!("{}{}", PROFILE_GROUP_PREFIX, name))
+        .is_err()
+    {
+        return false;
+    }
+
+    config::save(&keyfile)
+}
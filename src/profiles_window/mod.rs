@@ -0,0 +1,305 @@
+use std::{sync::mpsc, thread};
+
+use adw::{prelude::*, AlertDialog, ResponseAppearance};
+use gtk::{
+    glib::{self, clone, MainContext},
+    pango::EllipsizeMode,
+    Align, ApplicationWindow, Box, Builder, Button, Entry, Label, ListBox, ListBoxRow, Orientation
+};
+
+use crate::{
+    manager_window::show_message,
+    profiles::{self, ApplyReport, Profile}
+};
+
+// Create profiles window
+pub fn create(parent_window: &ApplicationWindow) -> ApplicationWindow {
+    // Create builder from UI file
+    let ui_src = include_str!("profiles.ui");
+    let builder = Builder::from_string(ui_src);
+
+    // Get window
+    let window = builder.object::<ApplicationWindow>("ProfilesWindow").unwrap();
+    window.set_transient_for(Some(parent_window));
+
+    // Fill profile list
+    let listbox = builder.object::<ListBox>("ProfileList").unwrap();
+    fill_profile_list(&window, &listbox);
+
+    // Init save profile button
+    let name_entry = builder.object::<Entry>("ProfileNameEntry").unwrap();
+    let save_button = builder.object::<Button>("SaveProfile").unwrap();
+
+    save_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[weak]
+        name_entry,
+        move |_| {
+            let name = name_entry.text().to_string();
+
+            if !profiles::is_valid_name(&name) {
+                show_message(
+                    &window,
+                    "Invalid profile name.",
+                    "Profile names can't be empty or contain brackets."
+                );
+                return;
+            }
+
+            // Disable window
+            window.set_sensitive(false);
+
+            let (tx, rx) = mpsc::channel();
+
+            // Hash enabled mods
+            thread::spawn(move || {
+                tx.send(Profile::capture(&name)).unwrap();
+            });
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                window,
+                #[weak]
+                listbox,
+                #[weak]
+                name_entry,
+                async move {
+                    if let Ok(result) = rx.recv() {
+                        window.set_sensitive(true);
+
+                        match result {
+                            Ok(profile) if profiles::save(&profile) => {
+                                name_entry.set_text("");
+                                fill_profile_list(&window, &listbox);
+                            },
+                            Ok(_) => show_message(
+                                &window,
+                                "Failed to save profile.",
+                                "The config file couldn't be written."
+                            ),
+                            Err(e) => show_message(
+                                &window,
+                                "Failed to save profile.",
+                                &format!("The enabled mods couldn't be read: {}.", e)
+                            )
+                        }
+                    }
+                }
+            ));
+        }
+    ));
+
+    window
+}
+
+// Fill the listbox with the saved profiles
+fn fill_profile_list(window: &ApplicationWindow, listbox: &ListBox) {
+    listbox.remove_all();
+
+    let profiles = profiles::list();
+
+    if profiles.is_empty() {
+        let empty_label = Label::builder()
+            .label("No profiles saved yet.")
+            .margin_top(12)
+            .margin_bottom(12)
+            .build();
+        empty_label.add_css_class("dim-label");
+
+        listbox.append(
+            &ListBoxRow::builder()
+                .child(&empty_label)
+                .activatable(false)
+                .build()
+        );
+        return;
+    }
+
+    for profile in profiles {
+        listbox.append(&create_row(window, listbox, profile));
+    }
+}
+
+// Create a list row for a profile
+fn create_row(window: &ApplicationWindow, listbox: &ListBox, profile: Profile) -> ListBoxRow {
+    // Create row contents
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+
+    let name = Label::builder()
+        .label(&profile.name)
+        .halign(Align::Start)
+        .hexpand(true)
+        .ellipsize(EllipsizeMode::End)
+        .build();
+    name.add_css_class("heading");
+    row_box.append(&name);
+
+    let mod_count = Label::builder()
+        .label(format!("{} mods", profile.mods.len()))
+        .halign(Align::End)
+        .build();
+    mod_count.add_css_class("dim-label");
+    row_box.append(&mod_count);
+
+    let apply_button = Button::with_label("Apply");
+    apply_button.set_tooltip_text(Some(
+        &profile
+            .mods
+            .iter()
+            .map(|m| m.filename.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    ));
+    row_box.append(&apply_button);
+
+    let delete_button = Button::from_icon_name("user-trash-symbolic");
+    delete_button.set_tooltip_text(Some("Delete profile"));
+    row_box.append(&delete_button);
+
+    // Apply profile in the background
+    apply_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[strong]
+        profile,
+        move |_| {
+            // Disable window
+            window.set_sensitive(false);
+
+            let (tx, rx) = mpsc::channel();
+
+            let name = profile.name.clone();
+            let profile = profile.clone();
+
+            thread::spawn(move || {
+                tx.send(profile.apply()).unwrap();
+            });
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                window,
+                async move {
+                    if let Ok(report) = rx.recv() {
+                        window.set_sensitive(true);
+                        show_apply_report(&window, &name, &report);
+                    }
+                }
+            ));
+        }
+    ));
+
+    // Delete profile after confirmation
+    delete_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        listbox,
+        #[strong(rename_to = name)]
+        profile.name,
+        move |_| {
+            let dialog = AlertDialog::builder()
+                .heading("Delete profile?")
+                .body(format!(
+                    "The profile \"{}\" will be deleted. Your mods won't be affected.",
+                    name
+                ))
+                .default_response("no")
+                .close_response("no")
+                .build();
+
+            dialog.add_responses(&[("yes", "_Delete"), ("no", "_Cancel")]);
+            dialog.set_response_appearance("yes", ResponseAppearance::Destructive);
+
+            // WORKAROUND: AlertDialog's close response doesn't work
+            dialog.connect_destroy(|dialog| {
+                dialog.emit_by_name::<()>("response", &[&dialog.close_response()]);
+            });
+
+            let signal = dialog.connect_response(
+                None,
+                clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    listbox,
+                    #[strong]
+                    name,
+                    move |_, result| {
+                        if result == "yes" {
+                            if !profiles::delete(&name) {
+                                show_message(
+                                    &window,
+                                    "Failed to delete profile.",
+                                    "The config file couldn't be written."
+                                );
+                            }
+
+                            fill_profile_list(&window, &listbox);
+                        }
+                    }
+                )
+            );
+
+            // WORKAROUND: AlertDialog's close response doesn't work
+            dialog.connect_response(None, move |d, _| {
+                d.block_signal(&signal);
+            });
+
+            dialog.present(Some(&window));
+        }
+    ));
+
+    ListBoxRow::builder().child(&row_box).activatable(false).build()
+}
+
+// Show what happened when applying a profile
+pub fn show_apply_report(window: &ApplicationWindow, name: &str, report: &ApplyReport) {
+    let mut body = format!(
+        "{} mods were enabled and {} mods were disabled.",
+        report.enabled, report.disabled
+    );
+
+    if !report.missing.is_empty() {
+        body += &format!(
+            "\n\nThe following mods couldn't be found:\n{}",
+            report.missing.join("\n")
+        );
+    }
+
+    if !report.changed.is_empty() {
+        body += &format!(
+            "\n\nThe following mods changed since the profile was saved:\n{}",
+            report.changed.join("\n")
+        );
+    }
+
+    if !report.failed.is_empty() {
+        body += &format!(
+            "\n\nThe following mods couldn't be moved:\n{}",
+            report.failed.join("\n")
+        );
+    }
+
+    if !report.settings_saved {
+        body += "\n\nThe injector settings couldn't be saved.";
+    }
+
+    let heading = if report.is_complete() {
+        format!("Profile \"{}\" applied.", name)
+    }
+    else {
+        format!("Profile \"{}\" was only partially applied.", name)
+    };
+
+    show_message(window, &heading, &body);
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, path::Path, sync::mpsc, thread};
+
+use adw::{prelude::*, Application};
+use gtk::{
+    gio::{DBusNodeInfo, SimpleAction},
+    glib::{self, clone, MainContext, Variant, VariantTy},
+    ApplicationWindow, Button
+};
+
+use crate::{manager_window, model::Model, mods, profiles, profiles_window};
+
+// D-Bus interface reporting the mod list
+const MOD_LIST_INTERFACE: &str = "io.github.brunoanc.eternalmodmanager.ModList";
+
+// Steam URL to launch DOOM Eternal
+const LAUNCH_GAME_URL: &str = "steam://rungameid/782330";
+
+// Add app actions, reachable over D-Bus through org.gtk.Actions
+pub fn add_actions(app: &Application, window: &ApplicationWindow, injector_button: &Button) {
+    // Create actions "enable-mod" and "disable-mod", taking the mod's filename
+    for (name, enabled) in [("enable-mod", true), ("disable-mod", false)] {
+        let action = SimpleAction::new(name, Some(VariantTy::STRING));
+
+        action.connect_activate(clone!(
+            #[weak]
+            window,
+            #[weak]
+            injector_button,
+            move |_, parameter| {
+                // Don't move mods while the mod injector runs
+                if crate::try_game_path().is_none() || !injector_button.is_sensitive() {
+                    return;
+                }
+
+                let filename = match parameter.and_then(|p| p.get::<String>()) {
+                    Some(f) => f,
+                    None => return
+                };
+
+                // Make sure the argument is a mod filename, not a path
+                if Path::new(&filename)
+                    .file_name()
+                    .is_none_or(|f| f != filename.as_str())
+                {
+                    return;
+                }
+
+                if let Err(e) = mods::set_enabled(&filename, enabled) {
+                    manager_window::show_message(
+                        &window,
+                        &format!(
+                            "Failed to {} \"{}\".",
+                            if enabled { "enable" } else { "disable" },
+                            filename
+                        ),
+                        &e.to_string()
+                    );
+                }
+            }
+        ));
+
+        app.add_action(&action);
+    }
+
+    // Create action "apply-profile", taking the profile's name
+    let action_apply_profile = SimpleAction::new("apply-profile", Some(VariantTy::STRING));
+
+    action_apply_profile.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        injector_button,
+        move |_, parameter| {
+            if crate::try_game_path().is_none() || !injector_button.is_sensitive() {
+                return;
+            }
+
+            let name = match parameter.and_then(|p| p.get::<String>()) {
+                Some(n) => n,
+                None => return
+            };
+
+            let profile = match profiles::list().into_iter().find(|p| p.name == name) {
+                Some(p) => p,
+                None => {
+                    manager_window::show_message(
+                        &window,
+                        "Failed to apply profile.",
+                        &format!("There's no profile named \"{}\".", name)
+                    );
+                    return;
+                }
+            };
+
+            // Apply profile in the background
+            let (tx, rx) = mpsc::channel();
+
+            thread::spawn(move || {
+                tx.send(profile.apply()).unwrap();
+            });
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                window,
+                async move {
+                    if let Ok(report) = rx.recv() {
+                        profiles_window::show_apply_report(&window, &name, &report);
+                    }
+                }
+            ));
+        }
+    ));
+
+    app.add_action(&action_apply_profile);
+
+    // Create action "inject" to run the mod injector as if its button was clicked
+    let action_inject = SimpleAction::new("inject", None);
+
+    action_inject.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        injector_button,
+        move |_, _| {
+            if crate::try_game_path().is_none() || !injector_button.is_sensitive() {
+                return;
+            }
+
+            window.present();
+            injector_button.emit_clicked();
+        }
+    ));
+
+    app.add_action(&action_inject);
+
+    // Create action "launch-game" to start DOOM Eternal through Steam
+    let action_launch_game = SimpleAction::new("launch-game", None);
+
+    action_launch_game.connect_activate(|_, _| {
+        thread::spawn(|| open::that(LAUNCH_GAME_URL));
+    });
+
+    app.add_action(&action_launch_game);
+}
+
+// Export the mod list on the app's D-Bus object, as read-only properties
+pub fn export_mod_list(app: &Application, model: &Model) {
+    let (connection, object_path) = match (app.dbus_connection(), app.dbus_object_path()) {
+        (Some(c), Some(p)) => (c, p),
+        _ => return
+    };
+
+    let interface_info = DBusNodeInfo::for_xml(include_str!("mod_list.xml"))
+        .ok()
+        .and_then(|n| n.lookup_interface(MOD_LIST_INTERFACE))
+        .unwrap();
+
+    // Report the model's state
+    let registration = connection
+        .register_object(&object_path, &interface_info)
+        .property(clone!(
+            #[strong]
+            model,
+            move |_, _, _, _, property| property_value(&model, property)
+        ))
+        .build();
+
+    if registration.is_err() {
+        return;
+    }
+
+    // Notify clients when the mod list changes
+    model.connect_items_changed(clone!(
+        #[strong]
+        connection,
+        move |_, _, _, _| {
+            let _ = connection.emit_signal(
+                None,
+                &object_path,
+                "org.freedesktop.DBus.Properties",
+                "PropertiesChanged",
+                Some(
+                    &(
+                        MOD_LIST_INTERFACE,
+                        HashMap::<String, Variant>::new(),
+                        vec!["Mods", "OnlineSafe", "GamePath"]
+                    )
+                        .to_variant()
+                )
+            );
+        }
+    ));
+}
+
+// Get the value of a mod list property
+fn property_value(model: &Model, property: &str) -> Variant {
+    let items = model.items();
+
+    match property {
+        "Mods" => items
+            .iter()
+            .map(|m| {
+                (
+                    m.filename().unwrap_or_default(),
+                    m.is_enabled(),
+                    m.is_valid(),
+                    m.is_valid() && m.is_online_safe()
+                )
+            })
+            .collect::<Vec<_>>()
+            .to_variant(),
+        "OnlineSafe" => items
+            .iter()
+            .filter(|m| m.is_enabled() && m.is_valid())
+            .all(|m| m.is_online_safe())
+            .to_variant(),
+        _ => crate::try_game_path()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+            .to_variant()
+    }
+}
@@ -0,0 +1,386 @@
+use std::{
+    fs,
+    path::{Path, PathBuf}
+};
+
+use gtk::glib;
+
+use crate::mods;
+
+// DOOM Eternal's Steam app ID
+const APP_ID: &str = "782330";
+
+// Value in a Valve KeyValues (VDF) file
+#[derive(Debug)]
+enum Value {
+    String(String),
+    Object(Vec<(String, Value)>)
+}
+
+impl Value {
+    // Get a child value by key, ignoring case like Steam does
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Value::String(_) => None
+        }
+    }
+
+    // Get a string value
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::Object(_) => None
+        }
+    }
+}
+
+// Split a VDF file into tokens, skipping comments
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut token = String::new();
+
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => token.push('\n'),
+                            Some('t') => token.push('\t'),
+                            Some(c) => token.push(c),
+                            None => break
+                        },
+                        c => token.push(c)
+                    }
+                }
+
+                tokens.push(token);
+            },
+            '{' | '}' => tokens.push(c.to_string()),
+            '/' if chars.peek() == Some(&'/') => {
+                // Skip comment
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            },
+            c if c.is_whitespace() => {},
+            c => {
+                // Unquoted token
+                let mut token = c.to_string();
+
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' || c == '{' || c == '}' {
+                        break;
+                    }
+
+                    token.push(c);
+                    chars.next();
+                }
+
+                tokens.push(token);
+            }
+        }
+    }
+
+    tokens
+}
+
+// Parse the entries of an object, until its closing brace or the end of the file
+fn parse_object(tokens: &mut impl Iterator<Item = String>) -> Value {
+    let mut entries = Vec::new();
+
+    while let Some(key) = tokens.next() {
+        if key == "}" {
+            break;
+        }
+
+        match tokens.next() {
+            Some(token) if token == "{" => entries.push((key, parse_object(tokens))),
+            Some(value) => entries.push((key, Value::String(value))),
+            None => break
+        }
+    }
+
+    Value::Object(entries)
+}
+
+// Parse a VDF file
+fn parse(text: &str) -> Value {
+    parse_object(&mut tokenize(text).into_iter())
+}
+
+// Get the folders Steam might be installed in
+fn steam_folders() -> Vec<PathBuf> {
+    #[cfg(target_os = "linux")]
+    let folders = {
+        let home = glib::home_dir();
+
+        vec![
+            home.join(".steam").join("steam"),
+            home.join(".steam").join("root"),
+            home.join(".local").join("share").join("Steam"),
+            // Flatpak Steam
+            home.join(".var")
+                .join("app")
+                .join("com.valvesoftware.Steam")
+                .join(".local")
+                .join("share")
+                .join("Steam"),
+            home.join(".var")
+                .join("app")
+                .join("com.valvesoftware.Steam")
+                .join(".steam")
+                .join("steam"),
+        ]
+    };
+
+    #[cfg(target_os = "windows")]
+    let folders = ["ProgramFiles(x86)", "ProgramFiles"]
+        .iter()
+        .filter_map(glib::getenv)
+        .map(|p| PathBuf::from(p).join("Steam"))
+        .collect::<Vec<PathBuf>>();
+
+    folders
+}
+
+// Get the Steam library folders listed in a Steam install
+fn library_folders(steam_folder: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_folder.to_path_buf()];
+
+    let text = match fs::read_to_string(steam_folder.join("steamapps").join("libraryfolders.vdf")) {
+        Ok(t) => t,
+        Err(_) => return libraries
+    };
+
+    if let Some(Value::Object(entries)) = parse(&text).get("libraryfolders") {
+        for (key, value) in entries {
+            // Skip non-library entries such as "contentstatsid"
+            if !key.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+
+            // Newer files have an object with a path per library, older ones just the path
+            let path = match value {
+                Value::Object(_) => value.get("path").and_then(Value::as_str),
+                Value::String(path) => Some(path.as_str())
+            };
+
+            if let Some(path) = path {
+                libraries.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    libraries
+}
+
+// Get DOOM Eternal's folder in a Steam library, if it's installed there
+fn game_folder(library: &Path) -> Option<PathBuf> {
+    let steamapps = library.join("steamapps");
+    let manifest = fs::read_to_string(steamapps.join(format!("appmanifest_{}.acf", APP_ID))).ok()?;
+
+    let install_dir = parse(&manifest)
+        .get("AppState")?
+        .get("installdir")?
+        .as_str()?
+        .to_owned();
+
+    let path = steamapps.join("common").join(install_dir);
+    mods::is_game_folder(&path).then_some(path)
+}
+
+// Find the DOOM Eternal installs in the Steam libraries
+pub fn find_game_folders() -> Vec<PathBuf> {
+    let mut game_folders: Vec<PathBuf> = Vec::new();
+
+    for steam_folder in steam_folders() {
+        if !steam_folder.is_dir() {
+            continue;
+        }
+
+        for library in library_folders(&steam_folder) {
+            let path = match game_folder(&library) {
+                Some(p) => p,
+                None => continue
+            };
+
+            // Steam's folder is usually reachable through several symlinks
+            let canonical = fs::canonicalize(&path).unwrap_or(path);
+
+            if !game_folders.contains(&canonical) {
+                game_folders.push(canonical);
+            }
+        }
+    }
+
+    game_folders
+}
+
+// Get the Steam build ID of the game installed in a game folder
+pub fn build_id(game_path: &Path) -> Option<String> {
+    let steamapps = game_path.parent()?.parent()?;
+    let manifest = fs::read_to_string(steamapps.join(format!("appmanifest_{}.acf", APP_ID))).ok()?;
+
+    parse(&manifest)
+        .get("AppState")?
+        .get("buildid")?
+        .as_str()
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    // Library list written by current Steam versions
+    const LIBRARY_FOLDERS: &str = r#"// Steam library folders
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"123456789"
+		"apps"
+		{
+			"228980"		"413085043"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"apps"
+		{
+			"782330"		"89024614957"
+		}
+	}
+	"contentstatsid"		"-123456789"
+}
+"#;
+
+    // Library list written by older Steam versions
+    const LEGACY_LIBRARY_FOLDERS: &str = r#""LibraryFolders"
+{
+	"TimeNextStatsReport"		"1600000000"
+	"ContentStatsID"		"-123456789"
+	"1"		"/mnt/games/SteamLibrary"
+	"2"		"/home/user/My \"Games\""
+}
+"#;
+
+    // App manifest with differently cased keys
+    const APP_MANIFEST: &str = r#""appstate"
+{
+	"appid"		"782330"
+	"Universe"		"1"
+	"name"		"DOOM Eternal"
+	"BuildID"		"12345678"
+	"InstallDir"		"DOOMEternal"
+}
+"#;
+
+    // Create an empty temporary folder for a test
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = env::temp_dir().join(format!("steam-test-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join("steamapps")).unwrap();
+        folder
+    }
+
+    #[test]
+    fn tokenizes_escapes_and_comments() {
+        assert_eq!(
+            tokenize("\"a\\\"b\" \"C:\\\\Steam\" // comment \"skipped\"\n{ key value }"),
+            vec!["a\"b", "C:\\Steam", "{", "key", "value", "}"]
+        );
+    }
+
+    #[test]
+    fn looks_up_keys_ignoring_case() {
+        let manifest = parse(APP_MANIFEST);
+        let app_state = manifest.get("AppState").unwrap();
+
+        assert_eq!(
+            app_state.get("installdir").and_then(Value::as_str),
+            Some("DOOMEternal")
+        );
+        assert_eq!(app_state.get("buildid").and_then(Value::as_str), Some("12345678"));
+        assert!(app_state.get("missing").is_none());
+    }
+
+    #[test]
+    fn reads_library_folders() {
+        let steam_folder = temp_folder("libraries");
+        fs::write(
+            steam_folder.join("steamapps").join("libraryfolders.vdf"),
+            LIBRARY_FOLDERS
+        )
+        .unwrap();
+
+        let libraries = library_folders(&steam_folder);
+        let _ = fs::remove_dir_all(&steam_folder);
+
+        assert_eq!(
+            libraries,
+            vec![
+                steam_folder,
+                PathBuf::from("C:\\Program Files (x86)\\Steam"),
+                PathBuf::from("D:\\SteamLibrary")
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_legacy_library_folders() {
+        let steam_folder = temp_folder("legacy-libraries");
+        fs::write(
+            steam_folder.join("steamapps").join("libraryfolders.vdf"),
+            LEGACY_LIBRARY_FOLDERS
+        )
+        .unwrap();
+
+        let libraries = library_folders(&steam_folder);
+        let _ = fs::remove_dir_all(&steam_folder);
+
+        assert_eq!(
+            libraries,
+            vec![
+                steam_folder,
+                PathBuf::from("/mnt/games/SteamLibrary"),
+                PathBuf::from("/home/user/My \"Games\"")
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_build_id() {
+        let library = temp_folder("build-id");
+        let game_path = library.join("steamapps").join("common").join("DOOMEternal");
+        fs::write(
+            library
+                .join("steamapps")
+                .join(format!("appmanifest_{}.acf", APP_ID)),
+            APP_MANIFEST
+        )
+        .unwrap();
+
+        let found = build_id(&game_path);
+        let missing = build_id(&library);
+        let _ = fs::remove_dir_all(&library);
+
+        assert_eq!(found.as_deref(), Some("12345678"));
+        assert_eq!(missing, None);
+    }
+}